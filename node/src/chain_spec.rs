@@ -1,13 +1,25 @@
+use crate::cli::ChainSpecParams;
 use contracts_node_runtime::{
-	AccountId, BalancesConfig, GenesisConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
+	call_index, AccountId, AssetsConfig, AuraConfig, BalancesCall, BalancesConfig,
+	ContractsCallFilterConfig, ContractsLimitsConfig, ContractsSponsorshipCall,
+	DefaultContractLimits, DefaultFeeModel, DevFeesConfig, DevRandomnessConfig, GenesisConfig,
+	GenesisNftsConfig, GenesisProxiesConfig, GrandpaConfig, ProxyType, Runtime, RuntimeCall,
+	SS58Prefix, SchedulerCall, Signature, SudoConfig, SystemCall, SystemConfig,
+	TransactionFeesConfig, EXISTENTIAL_DEPOSIT, TOKEN_DECIMALS, TOKEN_SYMBOL, WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_service::{ChainType, Properties};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::path::PathBuf;
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// The ID of the asset which is created at genesis to pay transaction fees with.
+pub const DEV_ASSET_ID: u32 = 1;
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -28,8 +40,76 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-pub fn development_config() -> Result<ChainSpec, String> {
+/// Generate an Aura authority key.
+pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+}
+
+/// The account of the 2-of-3 multisig of Alice, Bob and Charlie, which is endowed at genesis.
+pub fn dev_multisig() -> AccountId {
+	let mut signatories = vec![
+		get_account_id_from_seed::<sr25519::Public>("Alice"),
+		get_account_id_from_seed::<sr25519::Public>("Bob"),
+		get_account_id_from_seed::<sr25519::Public>("Charlie"),
+	];
+	signatories.sort();
+	pallet_multisig::Pallet::<Runtime>::multi_account_id(&signatories, 2)
+}
+
+/// The runtime calls contracts may dispatch via `call_runtime` from genesis on.
+fn contracts_allowed_calls() -> Vec<(u8, u8)> {
+	// Only the pallet and call index matter, the arguments are placeholders.
+	let dest = AccountId::from([0; 32]).into();
+	let call = || Box::new(RuntimeCall::System(SystemCall::remark { remark: vec![] }));
+	[
+		RuntimeCall::Balances(BalancesCall::transfer_allow_death { dest, value: 0 }),
+		// Lets contracts schedule calls, e.g. a `Contracts::call` to themselves, and cancel them.
+		// The scheduled call itself has to be allowed as well, see `AllowedContractsCalls`.
+		RuntimeCall::Scheduler(SchedulerCall::schedule {
+			when: 0,
+			maybe_periodic: None,
+			priority: 0,
+			call: call(),
+		}),
+		RuntimeCall::Scheduler(SchedulerCall::schedule_after {
+			after: 0,
+			maybe_periodic: None,
+			priority: 0,
+			call: call(),
+		}),
+		RuntimeCall::Scheduler(SchedulerCall::cancel { when: 0, index: 0 }),
+		// Lets contracts sponsor the fees of their callers.
+		RuntimeCall::ContractsSponsorship(ContractsSponsorshipCall::set_budget {
+			contract: AccountId::from([0; 32]),
+			budget: None,
+		}),
+	]
+	.iter()
+	.filter_map(call_index)
+	.collect()
+}
+
+/// Generate the chain properties, which client libraries use to render balances and addresses.
+///
+/// The defaults match the runtime's native token and `SS58Prefix`.
+pub fn properties(params: &ChainSpecParams) -> Properties {
+	let mut properties = Properties::new();
+	properties.insert(
+		"tokenSymbol".into(),
+		params.token_symbol.as_deref().unwrap_or(TOKEN_SYMBOL).into(),
+	);
+	properties
+		.insert("tokenDecimals".into(), params.token_decimals.unwrap_or(TOKEN_DECIMALS).into());
+	properties.insert(
+		"ss58Format".into(),
+		params.ss58_format.unwrap_or_else(|| SS58Prefix::get().into()).into(),
+	);
+	properties
+}
+
+pub fn development_config(params: &ChainSpecParams) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let feeless = params.feeless;
 
 	Ok(ChainSpec::from_genesis(
 		// Name
@@ -40,6 +120,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice")],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				// Pre-funded accounts
@@ -56,8 +138,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+					dev_multisig(),
 				],
 				true,
+				feeless,
 			)
 		},
 		// Bootnodes
@@ -69,14 +153,41 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Fork ID
 		None,
 		// Properties
-		None,
+		Some(properties(params)),
 		// Extensions
 		None,
 	))
 }
 
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
+/// Loads the chain spec at `path`, checking the parts of its genesis config which would
+/// otherwise only fail once the genesis block is built.
+pub fn from_json_file(path: PathBuf) -> Result<ChainSpec, String> {
+	let json = std::fs::read(&path)
+		.map_err(|e| format!("Unable to read the chain spec {}: {e}", path.display()))?;
+	let spec: serde_json::Value = serde_json::from_slice(&json)
+		.map_err(|e| format!("Unable to parse the chain spec {}: {e}", path.display()))?;
+
+	// Raw chain specs only contain the storage, which can't be checked.
+	if let Some(runtime) = spec.pointer("/genesis/runtime") {
+		if let Some(nfts) = runtime.get("genesisNfts") {
+			serde_json::from_value::<GenesisNftsConfig>(nfts.clone())
+				.map_err(|e| format!("Invalid `genesisNfts`: {e}"))?
+				.validate()?;
+		}
+		if let Some(proxies) = runtime.get("genesisProxies") {
+			serde_json::from_value::<GenesisProxiesConfig>(proxies.clone())
+				.map_err(|e| format!("Invalid `genesisProxies`: {e}"))?
+				.validate()?;
+		}
+	}
+
+	ChainSpec::from_json_bytes(json)
+}
+
+/// A local testnet with Alice, Bob and Charlie as Aura and Grandpa validators.
+pub fn local_testnet_config(params: &ChainSpecParams) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let feeless = params.feeless;
 
 	Ok(ChainSpec::from_genesis(
 		// Name
@@ -87,6 +198,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		move || {
 			testnet_genesis(
 				wasm_binary,
+				// Initial PoA authorities
+				vec![
+					authority_keys_from_seed("Alice"),
+					authority_keys_from_seed("Bob"),
+					authority_keys_from_seed("Charlie"),
+				],
 				// Sudo account
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				// Pre-funded accounts
@@ -103,8 +220,10 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+					dev_multisig(),
 				],
 				true,
+				feeless,
 			)
 		},
 		// Bootnodes
@@ -116,7 +235,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Fork ID
 		None,
 		// Properties
-		None,
+		Some(properties(params)),
 		// Extensions
 		None,
 	))
@@ -125,9 +244,11 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
 	_enable_println: bool,
+	feeless: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
 		system: SystemConfig {
@@ -138,11 +259,65 @@ fn testnet_genesis(
 			// Configure endowed accounts with initial balance of 1 << 60.
 			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
 		},
+		aura: AuraConfig {
+			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+		},
+		grandpa: GrandpaConfig {
+			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+		},
 		sudo: SudoConfig {
 			// Assign network admin rights.
-			key: Some(root_key),
+			key: Some(root_key.clone()),
 		},
 		transaction_payment: Default::default(),
-		assets: Default::default(),
+		assets: AssetsConfig {
+			// A sufficient asset with the existential deposit as its minimum balance, in which
+			// fees can be paid at a 1:1 rate via `ChargeAssetTxPayment`.
+			assets: vec![(DEV_ASSET_ID, root_key, true, EXISTENTIAL_DEPOSIT)],
+			metadata: vec![(
+				DEV_ASSET_ID,
+				b"Dev Stablecoin".to_vec(),
+				b"DUSD".to_vec(),
+				TOKEN_DECIMALS,
+			)],
+			accounts: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (DEV_ASSET_ID, k, 1 << 60))
+				.collect(),
+		},
+		transaction_fees: TransactionFeesConfig {
+			// Edit the chain spec to emulate the fees of another chain, e.g. set `multiplier`
+			// to adjust fees to congestion.
+			fee_model: DefaultFeeModel::get(),
+		},
+		contracts_limits: ContractsLimitsConfig {
+			// Edit the chain spec to emulate the limits of another chain,
+			// e.g. `"maxCodeLen": 131072`.
+			limits: DefaultContractLimits::get(),
+		},
+		contracts_call_filter: ContractsCallFilterConfig {
+			// Add `[palletIndex, callIndex]` pairs to the chain spec to allow further calls.
+			allowed_calls: contracts_allowed_calls(),
+		},
+		dev_randomness: DevRandomnessConfig {
+			// Set a seed in the chain spec to make the randomness deterministic.
+			seed: None,
+		},
+		genesis_nfts: GenesisNftsConfig {
+			// Add the owners of NFT collections and `[collection, item, owner]` triples to the
+			// chain spec to create collections and mint items at genesis.
+			collections: vec![],
+			items: vec![],
+		},
+		dev_fees: DevFeesConfig { feeless },
+		genesis_proxies: GenesisProxiesConfig {
+			// Bob may operate contracts on behalf of Alice.
+			proxies: vec![(
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Bob"),
+				ProxyType::Contracts,
+			)],
+		},
 	}
 }
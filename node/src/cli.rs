@@ -7,6 +7,35 @@ pub struct Cli {
 
 	#[command(flatten)]
 	pub run: RunCmd,
+
+	#[command(flatten)]
+	pub chain_spec: ChainSpecParams,
+
+	/// Re-execute every imported block while recording a storage proof and log the proof size
+	/// of each extrinsic, to catch extrinsics which would exceed the PoV limits of a parachain.
+	#[arg(long)]
+	pub report_proof_size: bool,
+}
+
+/// Overrides applied to the built-in `dev` and `local` chain specs.
+#[derive(Debug, Clone, clap::Args)]
+pub struct ChainSpecParams {
+	/// The token symbol reported in the chain properties.
+	#[arg(long, global = true, value_name = "SYMBOL")]
+	pub token_symbol: Option<String>,
+
+	/// The number of token decimals reported in the chain properties.
+	#[arg(long, global = true, value_name = "DECIMALS")]
+	pub token_decimals: Option<u8>,
+
+	/// The SS58 address format reported in the chain properties.
+	#[arg(long, global = true, value_name = "FORMAT")]
+	pub ss58_format: Option<u16>,
+
+	/// Don't charge transaction fees. Fees are still computed and reported, e.g. by
+	/// `payment_queryInfo`, but never withdrawn.
+	#[arg(long, global = true)]
+	pub feeless: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -38,4 +67,16 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Sub-commands concerned with benchmarking.
+	#[command(subcommand)]
+	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Try some command against runtime state.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Try some command against runtime state. Note: `try-runtime` feature must be enabled.
+	#[cfg(not(feature = "try-runtime"))]
+	TryRuntime,
 }
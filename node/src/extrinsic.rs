@@ -0,0 +1,71 @@
+//! Creates signed extrinsics on the node side, for the benchmarks and the dev RPCs.
+
+use codec::Encode;
+use contracts_node_runtime::{self as runtime, opaque::Block, AccountId, RuntimeCall};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{sr25519, Pair};
+use sp_runtime::{generic::Era, SaturatedConversion};
+
+/// Creates an extrinsic for `call` signed by `sender` with the nonce `nonce`, valid on top of
+/// the best block.
+pub fn create_extrinsic<C>(
+	client: &C,
+	sender: sr25519::Pair,
+	call: RuntimeCall,
+	nonce: u32,
+) -> Result<runtime::UncheckedExtrinsic, String>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: Core<Block>,
+{
+	let info = client.info();
+	// The on-chain version is used, as it may differ from the native one after an upgrade.
+	let version = client
+		.runtime_api()
+		.version(info.best_hash)
+		.map_err(|e| format!("Unable to query the runtime version: {e}"))?;
+
+	let period = runtime::BlockHashCount::get()
+		.checked_next_power_of_two()
+		.map(|c| c / 2)
+		.unwrap_or(2) as u64;
+	let extra: runtime::SignedExtra = (
+		frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+		frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+		frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+		frame_system::CheckGenesis::<runtime::Runtime>::new(),
+		frame_system::CheckEra::<runtime::Runtime>::from(Era::mortal(
+			period,
+			info.best_number.saturated_into(),
+		)),
+		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
+		frame_system::CheckWeight::<runtime::Runtime>::new(),
+		pallet_contracts_sponsorship::Sponsored::from(
+			pallet_asset_tx_payment::ChargeAssetTxPayment::<runtime::Runtime>::from(0, None),
+		),
+	);
+
+	let raw_payload = runtime::SignedPayload::from_raw(
+		call.clone(),
+		extra.clone(),
+		(
+			(),
+			version.spec_version,
+			version.transaction_version,
+			info.genesis_hash,
+			info.best_hash,
+			(),
+			(),
+			(),
+		),
+	);
+	let signature = raw_payload.using_encoded(|e| sender.sign(e));
+
+	Ok(runtime::UncheckedExtrinsic::new_signed(
+		call,
+		AccountId::from(sender.public()).into(),
+		runtime::Signature::Sr25519(signature),
+		extra,
+	))
+}
@@ -0,0 +1,312 @@
+//! Decodes the storage of ink! contracts via the storage layout in their metadata.
+//!
+//! ink! stores every storage root (the contract itself, each `Lazy` and each `Mapping`) under
+//! the SCALE-encoded root key, followed by the encoded mapping key for entries of a `Mapping`.
+//! `pallet_contracts` hashes these keys with `Blake2_128Concat`, so the root key can be
+//! recovered from the key in the child trie of the contract.
+//!
+//! The elements of arrays are stored one after another, like the fields of structs. Hash
+//! layouts are only emitted by ink! 3, whose storage can't be decoded: the remaining bytes of
+//! such an item are returned raw, marked as an unsupported layout.
+
+use scale_info::PortableRegistry;
+use serde::{Deserialize, Serialize};
+use sp_core::{hashing::blake2_128, Bytes};
+use std::collections::BTreeMap;
+
+/// The parts of the metadata of an ink! contract needed to decode its storage.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InkMetadata {
+	/// The types the storage layout refers to.
+	#[serde(flatten)]
+	registry: PortableRegistry,
+	/// The storage layout of the contract.
+	storage: Layout,
+}
+
+/// The storage layout of an ink! contract, as found in its metadata.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Layout {
+	Leaf { ty: u32 },
+	Root { root_key: Bytes, layout: Box<Layout> },
+	Hash(serde_json::Value),
+	Array { len: u32, layout: Box<Layout> },
+	Struct(StructLayout),
+	Enum { name: String, variants: BTreeMap<u8, StructLayout> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StructLayout {
+	name: String,
+	fields: Vec<FieldLayout>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FieldLayout {
+	name: String,
+	layout: Layout,
+}
+
+/// A storage item of an ink! contract, decoded via its metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedItem {
+	/// The path of the item in the storage layout, e.g. `Erc20.balances`.
+	pub path: String,
+	/// The encoded key of the entry if the item is a `Mapping`.
+	pub mapping_key: Option<Bytes>,
+	/// The decoded value.
+	pub value: serde_json::Value,
+}
+
+/// Decodes the storage items of a contract, see [`InkMetadata::decoder`].
+pub struct StorageDecoder<'a> {
+	metadata: &'a InkMetadata,
+	/// The storage roots in the layout with their path and key.
+	roots: Vec<(String, Vec<u8>, &'a Layout)>,
+}
+
+impl StorageDecoder<'_> {
+	/// Decodes the storage item with the key `key` in the child trie of the contract.
+	///
+	/// Returns `None` if `key` isn't the key of one of the storage roots in the layout.
+	pub fn decode(&self, key: &[u8], value: &[u8]) -> Option<Result<DecodedItem, String>> {
+		// Strip the `Blake2_128Concat` hash.
+		let (hash, key) = (key.get(..16)?, key.get(16..)?);
+		if blake2_128(key) != hash {
+			return None
+		}
+
+		let (path, root_key, layout) =
+			self.roots.iter().find(|(_, root_key, _)| key.starts_with(root_key))?;
+		let mapping_key = &key[root_key.len()..];
+
+		Some(self.metadata.decode_layout(layout, &mut &value[..]).map(|value| DecodedItem {
+			path: path.clone(),
+			mapping_key: (!mapping_key.is_empty()).then(|| mapping_key.to_vec().into()),
+			value,
+		}))
+	}
+}
+
+impl InkMetadata {
+	/// Returns a decoder for the storage items of contracts executing the code of the metadata.
+	pub fn decoder(&self) -> StorageDecoder<'_> {
+		let mut roots = Vec::new();
+		collect_roots(&self.storage, String::new(), &mut roots);
+		StorageDecoder { metadata: self, roots }
+	}
+
+	/// Decodes the values of `layout` from `data`, up to the next storage root.
+	fn decode_layout(
+		&self,
+		layout: &Layout,
+		data: &mut &[u8],
+	) -> Result<serde_json::Value, String> {
+		match layout {
+			Layout::Leaf { ty } => {
+				let value = scale_value::scale::decode_as_type(data, *ty, &self.registry)
+					.map_err(|e| format!("Unable to decode a value of type {ty}: {e}"))?;
+				serde_json::to_value(value).map_err(|e| e.to_string())
+			},
+			// Nested roots are stored under their own key and hence don't occupy any bytes.
+			Layout::Root { root_key, .. } => Ok(serde_json::json!({ "rootKey": root_key })),
+			Layout::Struct(layout) => self.decode_struct(layout, data),
+			Layout::Enum { name, variants } => {
+				let (&discriminant, rest) =
+					data.split_first().ok_or_else(|| format!("Unable to decode enum {name}"))?;
+				*data = rest;
+				let variant = variants
+					.get(&discriminant)
+					.ok_or_else(|| format!("Unknown variant {discriminant} of enum {name}"))?;
+				let mut value = serde_json::Map::new();
+				value.insert(variant.name.clone(), self.decode_struct(variant, data)?);
+				Ok(value.into())
+			},
+			Layout::Array { len, layout } => (0..*len)
+				.map(|_| self.decode_layout(layout, data))
+				.collect::<Result<Vec<_>, String>>()
+				.map(serde_json::Value::Array),
+			// The size of the values of a hash layout is unknown, so all that's left is returned.
+			Layout::Hash(_) => {
+				let raw = Bytes(data.to_vec());
+				*data = &[];
+				Ok(serde_json::json!({ "unsupportedLayout": "hash", "raw": raw }))
+			},
+		}
+	}
+
+	fn decode_struct(
+		&self,
+		layout: &StructLayout,
+		data: &mut &[u8],
+	) -> Result<serde_json::Value, String> {
+		layout
+			.fields
+			.iter()
+			.map(|field| Ok((field.name.clone(), self.decode_layout(&field.layout, data)?)))
+			.collect::<Result<serde_json::Map<_, _>, String>>()
+			.map(serde_json::Value::Object)
+	}
+}
+
+/// Collects the storage roots in `layout` with their path and key.
+fn collect_roots<'a>(
+	layout: &'a Layout,
+	path: String,
+	roots: &mut Vec<(String, Vec<u8>, &'a Layout)>,
+) {
+	match layout {
+		Layout::Root { root_key, layout } => {
+			// The root of the contract itself is named after it.
+			let path = match (path.is_empty(), &**layout) {
+				(true, Layout::Struct(layout)) => layout.name.clone(),
+				_ => path,
+			};
+			roots.push((path.clone(), root_key.to_vec(), layout));
+			collect_roots(layout, path, roots);
+		},
+		Layout::Struct(layout) =>
+			for field in &layout.fields {
+				collect_roots(&field.layout, format!("{path}.{}", field.name), roots);
+			},
+		Layout::Enum { variants, .. } =>
+			for variant in variants.values() {
+				for field in &variant.fields {
+					let path = format!("{path}.{}.{}", variant.name, field.name);
+					collect_roots(&field.layout, path, roots);
+				}
+			},
+		Layout::Array { layout, .. } => collect_roots(layout, format!("{path}[]"), roots),
+		Layout::Leaf { .. } | Layout::Hash(_) => {},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use serde_json::json;
+
+	// The metadata of the `flipper` and `erc20` examples of ink! 4.2, in the format
+	// `cargo contract build` writes it.
+	const FLIPPER: &str = include_str!("../fixtures/flipper.json");
+	const ERC20: &str = include_str!("../fixtures/erc20.json");
+
+	fn metadata(json: &str) -> InkMetadata {
+		serde_json::from_str(json).unwrap()
+	}
+
+	/// The key of the item under `key` in the child trie of a contract.
+	fn child_key(key: &[u8]) -> Vec<u8> {
+		[&blake2_128(key)[..], key].concat()
+	}
+
+	fn item(path: &str, mapping_key: Option<Vec<u8>>, value: serde_json::Value) -> DecodedItem {
+		DecodedItem { path: path.into(), mapping_key: mapping_key.map(Into::into), value }
+	}
+
+	#[test]
+	fn decodes_flipper() {
+		let metadata = metadata(FLIPPER);
+		let decoder = metadata.decoder();
+
+		let decoded = decoder.decode(&child_key(&[0; 4]), &true.encode()).unwrap();
+		assert_eq!(decoded, Ok(item("Flipper", None, json!({ "value": true }))));
+	}
+
+	#[test]
+	fn decodes_erc20() {
+		let metadata = metadata(ERC20);
+		let decoder = metadata.decoder();
+		let (alice, bob) = ([1u8; 32], [2u8; 32]);
+		let balances = [0x26, 0x23, 0xdc, 0xe7];
+		let allowances = [0xec, 0xa0, 0x21, 0xb7];
+
+		let decoded = decoder.decode(&child_key(&[0; 4]), &1_000u128.encode()).unwrap();
+		assert_eq!(
+			decoded,
+			Ok(item(
+				"Erc20",
+				None,
+				json!({
+					"total_supply": 1_000,
+					"balances": { "rootKey": "0x2623dce7" },
+					"allowances": { "rootKey": "0xeca021b7" },
+				})
+			))
+		);
+
+		let key = [&balances[..], &alice].concat();
+		let decoded = decoder.decode(&child_key(&key), &100u128.encode()).unwrap();
+		assert_eq!(decoded, Ok(item("Erc20.balances", Some(alice.to_vec()), json!(100))));
+
+		let key = [&allowances[..], &alice, &bob].concat();
+		let decoded = decoder.decode(&child_key(&key), &5u128.encode()).unwrap();
+		assert_eq!(decoded, Ok(item("Erc20.allowances", Some([alice, bob].concat()), json!(5))));
+	}
+
+	#[test]
+	fn ignores_unknown_keys() {
+		let metadata = metadata(ERC20);
+		let decoder = metadata.decoder();
+
+		assert_eq!(decoder.decode(&child_key(&[1, 2, 3, 4]), &[]), None);
+		// The hash doesn't match the key.
+		let mut key = child_key(&[0; 4]);
+		key[0] ^= 1;
+		assert_eq!(decoder.decode(&key, &[]), None);
+		assert_eq!(decoder.decode(&[0; 8], &[]), None);
+	}
+
+	#[test]
+	fn reports_values_which_do_not_match_the_layout() {
+		let metadata = metadata(ERC20);
+		let decoded = metadata.decoder().decode(&child_key(&[0; 4]), &[1, 2]).unwrap();
+		assert!(decoded.is_err(), "{decoded:?}");
+	}
+
+	/// The flipper metadata with `layout` as the layout of its only field.
+	fn with_field_layout(layout: serde_json::Value) -> InkMetadata {
+		let mut metadata: serde_json::Value = serde_json::from_str(FLIPPER).unwrap();
+		metadata["storage"]["root"]["layout"]["struct"]["fields"][0]["layout"] = layout;
+		serde_json::from_value(metadata).unwrap()
+	}
+
+	#[test]
+	fn decodes_arrays_element_by_element() {
+		// A `[bool; 3]` whose elements are laid out one by one.
+		let metadata = with_field_layout(json!({
+			"array": {
+				"layout": { "leaf": { "key": "0x00000000", "ty": 0 } },
+				"len": 3,
+				"offset": "0x00000000",
+			}
+		}));
+
+		let decoded = metadata.decoder().decode(&child_key(&[0; 4]), &[1, 0, 1]).unwrap();
+		assert_eq!(decoded, Ok(item("Flipper", None, json!({ "value": [true, false, true] }))));
+	}
+
+	#[test]
+	fn returns_hash_layouts_raw() {
+		let metadata = with_field_layout(json!({
+			"hash": {
+				"layout": { "leaf": { "key": "0x00000000", "ty": 0 } },
+				"offset": "0x00000000",
+				"strategy": { "hasher": "Blake2x256", "postfix": "", "prefix": "" },
+			}
+		}));
+
+		let decoded = metadata.decoder().decode(&child_key(&[0; 4]), &[1, 2]).unwrap();
+		assert_eq!(
+			decoded,
+			Ok(item(
+				"Flipper",
+				None,
+				json!({ "value": { "unsupportedLayout": "hash", "raw": "0x0102" } })
+			))
+		);
+	}
+}
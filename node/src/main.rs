@@ -4,9 +4,14 @@
 mod chain_spec;
 #[macro_use]
 mod service;
+mod benchmarking;
 mod cli;
 mod command;
+mod extrinsic;
+mod ink;
+mod pov;
 mod rpc;
+mod state_diff;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
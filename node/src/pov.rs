@@ -0,0 +1,146 @@
+//! Measures the storage proof size of blocks, i.e. the main part of the proof of validity (PoV)
+//! a parachain collator would have to submit for them.
+//!
+//! Blocks are re-executed on top of their parent's state while recording a storage proof.
+//! A trie node is only part of the proof once, so the proof size attributed to an extrinsic
+//! is the size of the trie nodes it was the first one to access in the block.
+
+use codec::Encode;
+use contracts_node_runtime::{opaque::Block, Hash, MAX_POV_SIZE};
+use futures::StreamExt;
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{traits::Header, DigestItem};
+use std::sync::Arc;
+
+/// The proof size of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProofSize {
+	/// The proof size of `on_initialize`, in bytes.
+	pub initialization: u64,
+	/// The proof size of the block's extrinsics.
+	pub extrinsics: Vec<ExtrinsicProofSize>,
+	/// The proof size of `on_finalize`, in bytes.
+	pub finalization: u64,
+	/// The proof size of the whole block, in bytes.
+	pub total: u64,
+	/// The encoded length of the block, which is part of the PoV as well.
+	pub block_len: u64,
+}
+
+/// The proof size of an extrinsic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicProofSize {
+	/// The index of the extrinsic in its block.
+	pub index: u32,
+	/// The size of the trie nodes first accessed by the extrinsic, in bytes.
+	pub proof_size: u64,
+	/// The encoded length of the extrinsic.
+	pub len: u64,
+}
+
+/// Re-executes the block `hash` while recording a storage proof and returns its proof size.
+pub fn measure<C>(client: &C, hash: Hash) -> Result<BlockProofSize, String>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+	C::Api: Core<Block> + BlockBuilder<Block>,
+{
+	let header = client
+		.header(hash)
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("Unknown block {hash}"))?;
+	let extrinsics = client
+		.block_body(hash)
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("The body of block {hash} is not available"))?;
+	let parent_hash = *header.parent_hash();
+
+	let mut api = client.runtime_api();
+	api.record_proof();
+	let proof_size = |api: &C::Api| {
+		api.proof_recorder()
+			.map_or(0, |recorder| recorder.estimate_encoded_size() as u64)
+	};
+
+	// The stored header carries the seal, which the block was built without.
+	let mut pre_header = header.clone();
+	if let Some(DigestItem::Seal(..)) = pre_header.digest().logs().last() {
+		pre_header.digest_mut().pop();
+	}
+	api.initialize_block(parent_hash, &pre_header).map_err(|e| e.to_string())?;
+	let initialization = proof_size(&api);
+
+	let mut sizes = Vec::with_capacity(extrinsics.len());
+	let mut before = initialization;
+	for (index, xt) in extrinsics.iter().enumerate() {
+		let len = xt.encoded_size() as u64;
+		api.apply_extrinsic(parent_hash, xt.clone()).map_err(|e| e.to_string())?;
+		let after = proof_size(&api);
+		sizes.push(ExtrinsicProofSize { index: index as u32, proof_size: after - before, len });
+		before = after;
+	}
+
+	api.finalize_block(parent_hash).map_err(|e| e.to_string())?;
+	let total = proof_size(&api);
+
+	Ok(BlockProofSize {
+		initialization,
+		extrinsics: sizes,
+		finalization: total - before,
+		total,
+		block_len: (header, extrinsics).encoded_size() as u64,
+	})
+}
+
+/// Logs the proof size of every imported block and of its extrinsics.
+///
+/// Warns about blocks whose PoV would exceed [`MAX_POV_SIZE`] on a parachain.
+pub async fn report_proof_sizes<C>(client: Arc<C>)
+where
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>,
+	C::Api: Core<Block> + BlockBuilder<Block>,
+{
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		let number = notification.header.number();
+		let size = match measure(&*client, notification.hash) {
+			Ok(size) => size,
+			Err(e) => {
+				log::warn!(target: "pov", "Unable to measure the proof size of block #{number}: {e}");
+				continue
+			},
+		};
+
+		for xt in &size.extrinsics {
+			log::info!(
+				target: "pov",
+				"Block #{number}, extrinsic {}: proof size {} bytes, length {} bytes",
+				xt.index,
+				xt.proof_size,
+				xt.len,
+			);
+		}
+		let pov_size = size.total + size.block_len;
+		log::info!(
+			target: "pov",
+			"Block #{number} ({}): proof size {} bytes, PoV size {pov_size} bytes ({}% of {MAX_POV_SIZE})",
+			notification.hash,
+			size.total,
+			pov_size * 100 / MAX_POV_SIZE as u64,
+		);
+		if pov_size > MAX_POV_SIZE as u64 {
+			log::warn!(
+				target: "pov",
+				"Block #{number} exceeds the maximum PoV size of a parachain block",
+			);
+		}
+	}
+}
@@ -7,9 +7,11 @@
 
 use std::sync::Arc;
 
-use contracts_node_runtime::{opaque::Block, AccountId, Balance, Index};
+use contracts_node_runtime::{opaque::Block, AccountId, Balance, Hash, Index};
+use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
-use sc_client_api::BlockBackend;
+use sc_client_api::{BlockBackend, BlockchainEvents, StorageProvider};
+use sc_consensus_manual_seal::EngineCommand;
 use sc_rpc::dev::{Dev, DevApiServer};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
@@ -18,6 +20,9 @@ use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 
 pub use sc_rpc_api::DenyUnsafe;
 
+pub mod contracts;
+pub mod dev;
+
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
 	/// The client instance to use.
@@ -26,29 +31,35 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Channel to request new blocks, if the chain is run with instant seal.
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
+pub fn create_full<C, P, BE>(
 	deps: FullDeps<C, P>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
+	BE: sc_client_api::Backend<Block> + 'static,
 	C: ProvideRuntimeApi<Block>,
-	C: BlockBackend<Block>,
+	C: BlockBackend<Block> + BlockchainEvents<Block> + StorageProvider<Block, BE>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
-	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + 'static,
+	C::Api: BlockBuilder<Block> + sp_api::Core<Block> + sp_api::Metadata<Block>,
+	C::Api: contracts_node_runtime::ContractsAddressApi<Block, AccountId, Hash>,
+	P: TransactionPool<Block = Block> + 'static,
 {
+	use contracts::{ContractsInspect, ContractsInspectApiServer};
+	use dev::{DevChain, DevChainApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, command_sink } = deps;
 
-	module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
+	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
 	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
@@ -57,7 +68,9 @@ where
 	// `module.merge(YourRpcTrait::into_rpc(YourRpcStruct::new(ReferenceToClient, ...)))?;`
 
 	// Dev RPC API extension
-	module.merge(Dev::new(client, deny_unsafe).into_rpc())?;
+	module.merge(Dev::new(client.clone(), deny_unsafe).into_rpc())?;
+	module.merge(DevChain::new(client.clone(), pool, command_sink, deny_unsafe).into_rpc())?;
+	module.merge(ContractsInspect::new(client, deny_unsafe).into_rpc())?;
 
 	Ok(module)
 }
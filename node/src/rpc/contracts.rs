@@ -0,0 +1,536 @@
+//! RPC methods which inspect the contracts deployed on a development chain.
+//!
+//! `pallet_contracts` doesn't expose most of what it stores about contracts, so its storage is
+//! read raw and decoded into mirrors of its storage types. The methods reading storage are
+//! unsafe, as they iterate over whole storage maps.
+//!
+//! The storage of ink! contracts can be decoded once the metadata of their code is registered
+//! via `contracts_registerMetadata`. Registered metadata is kept in memory until the node stops.
+
+use std::{
+	collections::HashMap,
+	marker::PhantomData,
+	sync::{Arc, RwLock},
+};
+
+use crate::ink::{DecodedItem, InkMetadata};
+use codec::{Compact, Decode, Encode};
+use contracts_node_runtime::{opaque::Block, AccountId, Balance, ContractsAddressApi, Hash};
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use sc_client_api::StorageProvider;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::{twox_128, twox_64},
+	storage::{ChildInfo, StorageKey},
+	Bytes,
+};
+
+/// The error code returned by all contracts RPC methods.
+const CONTRACTS_RPC_ERROR: i32 = 7100;
+
+/// The maximum number of entries returned per page.
+const PAGE_SIZE: usize = 100;
+
+/// The name of `pallet_contracts` in the runtime.
+const PALLET: &str = "Contracts";
+
+fn error(message: impl Into<String>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(CONTRACTS_RPC_ERROR, message.into(), None::<()>)).into()
+}
+
+/// A page of entries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+	/// The entries, ordered by their storage key.
+	pub items: Vec<T>,
+	/// The storage key of the last entry, to pass as `start_key` to get the next page. `None` if
+	/// there are no further entries.
+	pub last_key: Option<Bytes>,
+}
+
+impl<T> Page<T> {
+	/// Creates a page of `items`, which were read from the storage keys `keys`.
+	fn new(items: Vec<T>, keys: &[StorageKey]) -> Self {
+		// Only a full page may be followed by further entries.
+		let last_key =
+			keys.last().filter(|_| keys.len() == PAGE_SIZE).map(|key| key.0.clone().into());
+		Self { items, last_key }
+	}
+}
+
+/// A deployed contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractEntry {
+	/// The address of the contract.
+	pub address: AccountId,
+	/// The hash of the code the contract executes.
+	pub code_hash: Hash,
+	/// The account holding the storage deposit of the contract.
+	pub deposit_account: AccountId,
+	/// The number of storage items of the contract.
+	pub storage_items: u32,
+	/// The number of bytes the storage items of the contract occupy.
+	pub storage_bytes: u32,
+	/// The storage deposit held for the contract, including the deposit for its base storage.
+	pub deposit: Balance,
+}
+
+/// A code uploaded to the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeEntry {
+	/// The hash of the code.
+	pub code_hash: Hash,
+	/// The account which uploaded the code and paid its deposit.
+	pub owner: AccountId,
+	/// The deposit held for the code.
+	pub deposit: Balance,
+	/// The size of the code as uploaded, in bytes.
+	pub size: u32,
+	/// The number of contracts executing the code.
+	pub refcount: u64,
+	/// Whether the code may use non-deterministic features.
+	pub determinism: Determinism,
+}
+
+/// An item in the storage of a contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageItem {
+	/// The key of the item in the child trie of the contract.
+	pub key: Bytes,
+	/// The encoded value.
+	pub value: Bytes,
+	/// The decoded item, if metadata is registered for the code of the contract.
+	pub decoded: Option<DecodedItem>,
+	/// Why the item couldn't be decoded via the registered metadata.
+	pub decode_error: Option<String>,
+}
+
+// The mirrors below follow the storage types of `pallet-contracts` on the `polkadot-v0.9.43`
+// branch and have to be updated along with it. The `decodes_contracts_storage` test checks them
+// against storage written by the runtime.
+
+/// Mirrors `pallet_contracts::Determinism`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Serialize, Deserialize)]
+pub enum Determinism {
+	/// The code can only be executed deterministically.
+	Enforced,
+	/// The code may only be executed off-chain, as it may behave non-deterministically.
+	Relaxed,
+}
+
+/// Mirrors `pallet_contracts::storage::ContractInfo`.
+#[derive(Decode)]
+pub(crate) struct ContractInfo {
+	pub trie_id: Vec<u8>,
+	deposit_account: AccountId,
+	code_hash: Hash,
+	storage_bytes: u32,
+	storage_items: u32,
+	storage_byte_deposit: Balance,
+	storage_item_deposit: Balance,
+	storage_base_deposit: Balance,
+}
+
+/// Mirrors `pallet_contracts::wasm::OwnerInfo`.
+#[derive(Decode)]
+struct OwnerInfo {
+	owner: AccountId,
+	#[codec(compact)]
+	deposit: Balance,
+	#[codec(compact)]
+	refcount: u64,
+}
+
+/// Mirrors `pallet_contracts::wasm::PrefabWasmModule`.
+#[derive(Decode)]
+struct PrefabWasmModule {
+	#[codec(compact)]
+	_instruction_weights_version: u32,
+	#[codec(compact)]
+	_initial: u32,
+	#[codec(compact)]
+	_maximum: u32,
+	_code: Vec<u8>,
+	determinism: Determinism,
+}
+
+/// Returns the prefix of the keys of the storage map `item` of `pallet_contracts`.
+pub(crate) fn map_prefix(item: &str) -> StorageKey {
+	StorageKey([twox_128(PALLET.as_bytes()), twox_128(item.as_bytes())].concat())
+}
+
+/// Contracts RPC methods.
+#[rpc(server)]
+pub trait ContractsInspectApi {
+	/// Returns the contracts deployed at block `at` (the best block if omitted), [`PAGE_SIZE`]
+	/// per page, starting after the storage key `start_key` or from the first contract.
+	#[method(name = "contracts_listContracts")]
+	fn list_contracts(
+		&self,
+		start_key: Option<Bytes>,
+		at: Option<Hash>,
+	) -> RpcResult<Page<ContractEntry>>;
+
+	/// Returns the codes uploaded at block `at` (the best block if omitted), [`PAGE_SIZE`] per
+	/// page, starting after the storage key `start_key` or from the first code.
+	#[method(name = "contracts_listCodes")]
+	fn list_codes(&self, start_key: Option<Bytes>, at: Option<Hash>) -> RpcResult<Page<CodeEntry>>;
+
+	/// Returns the items in the storage of the contract `address` at block `at` (the best block
+	/// if omitted) whose key starts with `prefix`, [`PAGE_SIZE`] per page, starting after the key
+	/// `start_key` or from the first item.
+	///
+	/// Keys are the raw keys in the child trie of the contract, i.e. hashed by
+	/// `pallet_contracts`. Items are decoded if metadata is registered for the code of the
+	/// contract.
+	#[method(name = "contracts_dumpStorage")]
+	fn dump_storage(
+		&self,
+		address: AccountId,
+		prefix: Option<Bytes>,
+		start_key: Option<Bytes>,
+		at: Option<Hash>,
+	) -> RpcResult<Page<StorageItem>>;
+
+	/// Registers the ink! metadata of the code `code_hash`, i.e. the `<contract>.json` file
+	/// generated by `cargo contract build`, to decode the storage of contracts executing it.
+	#[method(name = "contracts_registerMetadata")]
+	fn register_metadata(&self, code_hash: Hash, metadata: InkMetadata) -> RpcResult<()>;
+
+	/// Returns the address of the contract `deployer` instantiates from the code `code_hash`
+	/// with the constructor input `input_data` and the salt `salt`.
+	///
+	/// The address is derived by the `AddressGenerator` of the runtime at block `at` (the best
+	/// block if omitted), like `Contracts::instantiate` does.
+	#[method(name = "contracts_predictAddress")]
+	fn predict_address(
+		&self,
+		deployer: AccountId,
+		code_hash: Hash,
+		input_data: Bytes,
+		salt: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<AccountId>;
+}
+
+/// Implements the [`ContractsInspectApiServer`] RPC trait.
+pub struct ContractsInspect<C, BE> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	metadata: RwLock<HashMap<Hash, InkMetadata>>,
+	_backend: PhantomData<BE>,
+}
+
+impl<C, BE> ContractsInspect<C, BE>
+where
+	BE: sc_client_api::Backend<Block> + 'static,
+	C: HeaderBackend<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+{
+	/// Creates a new instance of the contracts RPC methods.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		Self { client, deny_unsafe, metadata: Default::default(), _backend: PhantomData }
+	}
+
+	/// Reads the info `pallet_contracts` stores about the contract `address` at `at`.
+	fn contract_info(&self, at: Hash, address: &AccountId) -> RpcResult<ContractInfo> {
+		let encoded = address.encode();
+		let key = StorageKey(
+			[&map_prefix("ContractInfoOf").0[..], &twox_64(&encoded), &encoded].concat(),
+		);
+		self.client
+			.storage(at, &key)
+			.map_err(|e| error(format!("Unable to read {PALLET}::ContractInfoOf: {e}")))?
+			.ok_or_else(|| error(format!("{address} is not a contract")))
+			.and_then(|data| {
+				ContractInfo::decode(&mut &data.0[..])
+					.map_err(|e| error(format!("Unable to decode {PALLET}::ContractInfoOf: {e}")))
+			})
+	}
+
+	/// Returns a page of the entries of the storage map `item` of `pallet_contracts` after the
+	/// storage key `start_key`, with the map key, i.e. the storage key without the prefix and
+	/// hash of the map.
+	fn map_page<K: Decode, V: Decode>(
+		&self,
+		at: Hash,
+		item: &str,
+		hash_len: usize,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Page<(K, V)>> {
+		let prefix = map_prefix(item);
+		let start_key = start_key.map(|key| StorageKey(key.0));
+		let pairs: Vec<_> = self
+			.client
+			.storage_pairs(at, Some(&prefix), start_key.as_ref())
+			.map_err(|e| error(format!("Unable to read {PALLET}::{item}: {e}")))?
+			.take(PAGE_SIZE)
+			.collect();
+		let entries = pairs
+			.iter()
+			.map(|(key, value)| {
+				let key = K::decode(&mut &key.0[prefix.0.len() + hash_len..]).map_err(|e| {
+					error(format!("Unable to decode a key of {PALLET}::{item}: {e}"))
+				})?;
+				let value = V::decode(&mut &value.0[..]).map_err(|e| {
+					error(format!("Unable to decode a value of {PALLET}::{item}: {e}"))
+				})?;
+				Ok((key, value))
+			})
+			.collect::<RpcResult<_>>()?;
+		let keys: Vec<_> = pairs.into_iter().map(|(key, _)| key).collect();
+		Ok(Page::new(entries, &keys))
+	}
+
+	/// Reads the value of `key` in the storage map `item` of `pallet_contracts`, which uses the
+	/// `Identity` hasher.
+	fn map_value<V: Decode>(&self, at: Hash, item: &str, key: &Hash) -> RpcResult<Option<V>> {
+		let key = StorageKey([&map_prefix(item).0[..], key.as_bytes()].concat());
+		self.client
+			.storage(at, &key)
+			.map_err(|e| error(format!("Unable to read {PALLET}::{item}: {e}")))?
+			.map(|data| V::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| error(format!("Unable to decode {PALLET}::{item}: {e}")))
+	}
+}
+
+impl<C, BE> ContractsInspectApiServer for ContractsInspect<C, BE>
+where
+	BE: sc_client_api::Backend<Block> + 'static,
+	C: HeaderBackend<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ContractsAddressApi<Block, AccountId, Hash>,
+{
+	fn list_contracts(
+		&self,
+		start_key: Option<Bytes>,
+		at: Option<Hash>,
+	) -> RpcResult<Page<ContractEntry>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		// `ContractInfoOf` uses the `Twox64Concat` hasher.
+		let contracts: Page<(AccountId, ContractInfo)> =
+			self.map_page(at, "ContractInfoOf", 8, start_key)?;
+		let items = contracts
+			.items
+			.into_iter()
+			.map(|(address, info)| ContractEntry {
+				address,
+				code_hash: info.code_hash,
+				deposit_account: info.deposit_account,
+				storage_items: info.storage_items,
+				storage_bytes: info.storage_bytes,
+				deposit: info.storage_byte_deposit +
+					info.storage_item_deposit +
+					info.storage_base_deposit,
+			})
+			.collect();
+		Ok(Page { items, last_key: contracts.last_key })
+	}
+
+	fn list_codes(&self, start_key: Option<Bytes>, at: Option<Hash>) -> RpcResult<Page<CodeEntry>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		// The code maps use the `Identity` hasher.
+		let owners: Page<(Hash, OwnerInfo)> = self.map_page(at, "OwnerInfoOf", 0, start_key)?;
+		let items = owners
+			.items
+			.into_iter()
+			.map(|(code_hash, owner_info)| {
+				// Only the length prefix of the code is decoded.
+				let Compact(size) = self
+					.map_value::<Compact<u32>>(at, "PristineCode", &code_hash)?
+					.ok_or_else(|| error(format!("The code {code_hash} is missing")))?;
+				let module = self
+					.map_value::<PrefabWasmModule>(at, "CodeStorage", &code_hash)?
+					.ok_or_else(|| error(format!("The code {code_hash} is missing")))?;
+				Ok(CodeEntry {
+					code_hash,
+					owner: owner_info.owner,
+					deposit: owner_info.deposit,
+					size,
+					refcount: owner_info.refcount,
+					determinism: module.determinism,
+				})
+			})
+			.collect::<RpcResult<_>>()?;
+		Ok(Page { items, last_key: owners.last_key })
+	}
+
+	fn dump_storage(
+		&self,
+		address: AccountId,
+		prefix: Option<Bytes>,
+		start_key: Option<Bytes>,
+		at: Option<Hash>,
+	) -> RpcResult<Page<StorageItem>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let info = self.contract_info(at, &address)?;
+		let child_info = ChildInfo::new_default(&info.trie_id);
+		let prefix = prefix.map(|prefix| StorageKey(prefix.0));
+		let start_key = start_key.map(|key| StorageKey(key.0));
+		let keys: Vec<_> = self
+			.client
+			.child_storage_keys(at, child_info.clone(), prefix.as_ref(), start_key.as_ref())
+			.map_err(|e| error(format!("Unable to read the storage of {address}: {e}")))?
+			.take(PAGE_SIZE)
+			.collect();
+
+		let metadata = self.metadata.read().map_err(|_| error("The metadata lock is poisoned"))?;
+		let decoder = metadata.get(&info.code_hash).map(InkMetadata::decoder);
+		let items = keys
+			.iter()
+			.map(|key| {
+				let value = self
+					.client
+					.child_storage(at, &child_info, key)
+					.map_err(|e| error(format!("Unable to read the storage of {address}: {e}")))?
+					.unwrap_or_default();
+				let (decoded, decode_error) =
+					match decoder.as_ref().and_then(|decoder| decoder.decode(&key.0, &value.0)) {
+						Some(Ok(decoded)) => (Some(decoded), None),
+						Some(Err(e)) => (None, Some(e)),
+						None => (None, None),
+					};
+				Ok(StorageItem {
+					key: key.0.clone().into(),
+					value: value.0.into(),
+					decoded,
+					decode_error,
+				})
+			})
+			.collect::<RpcResult<_>>()?;
+		Ok(Page::new(items, &keys))
+	}
+
+	fn register_metadata(&self, code_hash: Hash, metadata: InkMetadata) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.metadata
+			.write()
+			.map_err(|_| error("The metadata lock is poisoned"))?
+			.insert(code_hash, metadata);
+		Ok(())
+	}
+
+	fn predict_address(
+		&self,
+		deployer: AccountId,
+		code_hash: Hash,
+		input_data: Bytes,
+		salt: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<AccountId> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client
+			.runtime_api()
+			.contract_address(at, deployer, code_hash, input_data.0, salt.0)
+			.map_err(|e| error(format!("Unable to derive the contract address: {e}")))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use contracts_node_runtime::{
+		Balances, BuildStorage, Contracts, GenesisConfig, RuntimeOrigin, System, Weight,
+	};
+	use pallet_contracts::{CollectEvents, DebugInfo};
+	use pallet_contracts_primitives::Code;
+
+	/// `(module (import "env" "memory" (memory 1 1)) (func (export "deploy")) (func (export
+	/// "call")))`, the smallest code `pallet_contracts` accepts.
+	const CODE: [u8; 65] = [
+		0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+		0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: `fn()`
+		0x02, 0x10, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02,
+		0x01, 0x01, 0x01, // import section: `env.memory`
+		0x03, 0x03, 0x02, 0x00, 0x00, // function section
+		0x07, 0x11, 0x02, 0x06, 0x64, 0x65, 0x70, 0x6c, 0x6f, 0x79, 0x00, 0x00, 0x04, 0x63, 0x61,
+		0x6c, 0x6c, 0x00, 0x01, // export section: `deploy` and `call`
+		0x0a, 0x07, 0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b, // code section: empty bodies
+	];
+
+	fn read<V: Decode>(key: Vec<u8>) -> V {
+		let value = sp_io::storage::get(&key).expect("the item exists");
+		codec::DecodeAll::decode_all(&mut &value[..]).expect("the mirror matches the item")
+	}
+
+	#[test]
+	fn decodes_contracts_storage() {
+		let alice = AccountId::new([1; 32]);
+		let mut ext: sp_io::TestExternalities =
+			GenesisConfig::default().build_storage().unwrap().into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			Balances::force_set_balance(RuntimeOrigin::root(), alice.clone().into(), 1 << 100)
+				.unwrap();
+
+			let code_hash = Contracts::bare_upload_code(
+				alice.clone(),
+				CODE.to_vec(),
+				None,
+				pallet_contracts::Determinism::Enforced,
+			)
+			.unwrap()
+			.code_hash;
+			let address = Contracts::bare_instantiate(
+				alice.clone(),
+				0,
+				Weight::from_parts(100_000_000_000, 1024 * 1024),
+				None,
+				Code::Existing(code_hash),
+				vec![],
+				vec![],
+				DebugInfo::Skip,
+				CollectEvents::Skip,
+			)
+			.result
+			.unwrap()
+			.account_id;
+
+			let encoded = address.encode();
+			let info: ContractInfo =
+				read([&map_prefix("ContractInfoOf").0[..], &twox_64(&encoded), &encoded].concat());
+			assert_eq!(info.code_hash, code_hash);
+			assert_eq!(info.storage_items, 0);
+			assert_ne!(info.deposit_account, address);
+			assert!(info.storage_base_deposit > 0);
+
+			let owner_info: OwnerInfo =
+				read([&map_prefix("OwnerInfoOf").0[..], code_hash.as_bytes()].concat());
+			assert_eq!(owner_info.owner, alice);
+			assert_eq!(owner_info.refcount, 1);
+			assert!(owner_info.deposit > 0);
+
+			let module: PrefabWasmModule =
+				read([&map_prefix("CodeStorage").0[..], code_hash.as_bytes()].concat());
+			assert_eq!(module.determinism, Determinism::Enforced);
+		});
+	}
+
+	#[test]
+	fn page_continues_after_a_full_page_only() {
+		let keys: Vec<_> = (0..PAGE_SIZE as u8).map(|i| StorageKey(vec![i])).collect();
+		let page = Page::new(vec![(); PAGE_SIZE], &keys);
+		assert_eq!(page.last_key, Some(vec![PAGE_SIZE as u8 - 1].into()));
+
+		let page = Page::new(vec![(); 2], &keys[..2]);
+		assert_eq!(page.last_key, None);
+	}
+}
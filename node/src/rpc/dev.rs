@@ -0,0 +1,358 @@
+//! RPC methods which manipulate a development chain.
+//!
+//! Changes which require root are dispatched via `Sudo`, signed by the development account
+//! which is configured as the sudo key (Alice for the built-in chain specs). All methods are
+//! unsafe and hence only available if the node is run with `--rpc-methods=unsafe` or `--dev`.
+
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
+
+use crate::{
+	extrinsic,
+	pov::{self, BlockProofSize},
+	state_diff::{self, StateDiff},
+};
+use codec::{Decode, Encode};
+use contracts_node_runtime::{
+	self as runtime, opaque::Block, AccountId, ContractsCall, DevRandomnessCall, Hash, Index,
+	RuntimeCall, RuntimeEvent, SystemCall, Weight,
+};
+use frame_system::{EventRecord, Phase};
+use futures::{
+	channel::{mpsc, oneshot},
+	SinkExt, StreamExt,
+};
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use sc_client_api::{BlockBackend, BlockchainEvents, StorageProvider};
+use sc_consensus_manual_seal::EngineCommand;
+use sc_rpc_api::DenyUnsafe;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource, TransactionStatus};
+use serde::{Deserialize, Serialize};
+use sp_api::{Core, Metadata, ProvideRuntimeApi, RuntimeVersion};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, sr25519, storage::StorageKey, Bytes, Pair};
+use sp_keyring::Sr25519Keyring;
+use substrate_frame_rpc_system::AccountNonceApi;
+
+/// The error code returned by all dev RPC methods.
+const DEV_RPC_ERROR: i32 = 7000;
+
+fn error(message: impl Into<String>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(DEV_RPC_ERROR, message.into(), None::<()>)).into()
+}
+
+/// The Wasm code of a runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuntimeCode {
+	/// The hex-encoded Wasm blob.
+	Bytes(Bytes),
+	/// The path of a Wasm file on the host of the node.
+	Path(PathBuf),
+}
+
+impl RuntimeCode {
+	fn into_bytes(self) -> std::io::Result<Vec<u8>> {
+		match self {
+			RuntimeCode::Bytes(bytes) => Ok(bytes.0),
+			RuntimeCode::Path(path) => std::fs::read(path),
+		}
+	}
+}
+
+/// Dev RPC methods.
+#[rpc(server)]
+pub trait DevChainApi {
+	/// Sets the code of the runtime to `code` and authors another block, in which the
+	/// migrations of the new runtime are executed.
+	///
+	/// `code` is either the hex-encoded Wasm blob or the path of a Wasm file on the host of
+	/// the node. Compressed blobs (`*.compact.compressed.wasm`) are supported as well.
+	///
+	/// Returns the version of the new runtime.
+	#[method(name = "dev_upgradeRuntime")]
+	async fn upgrade_runtime(&self, code: RuntimeCode) -> RpcResult<RuntimeVersion>;
+
+	/// Re-executes the block `hash` (the best block if omitted) while recording a storage
+	/// proof and returns the proof size of the block and of each of its extrinsics.
+	#[method(name = "dev_proofSize")]
+	fn proof_size(&self, hash: Option<Hash>) -> RpcResult<BlockProofSize>;
+
+	/// Sets the seed of the randomness contracts observe, making it deterministic. Passing
+	/// `null` restores the non-deterministic randomness.
+	///
+	/// The random value for a subject is the hash of the seed, the block number and the
+	/// subject. Returns the hash of the block in which the seed was set.
+	#[method(name = "dev_setRandomSeed")]
+	async fn set_random_seed(&self, seed: Option<Hash>) -> RpcResult<Hash>;
+
+	/// Fixes the random value contracts observe to `randomness`, for every subject and
+	/// regardless of the seed, e.g. to force the outcome of a draw. Passing `null` restores the
+	/// seeded or non-deterministic randomness.
+	///
+	/// Returns the hash of the block in which the value was set.
+	#[method(name = "dev_setRandomness")]
+	async fn set_randomness(&self, randomness: Option<Hash>) -> RpcResult<Hash>;
+
+	/// Returns the changes to the state between the blocks `from` and `to` (the best block if
+	/// omitted), with the values before and after.
+	///
+	/// Top-level keys are annotated with the pallet and storage item they belong to. Changes to
+	/// the storage of contracts are reported per contract.
+	#[method(name = "dev_stateDiff")]
+	fn state_diff(&self, from: Hash, to: Option<Hash>) -> RpcResult<StateDiff>;
+
+	/// Replaces the code of the contract `address` with the uploaded code `code_hash`, without
+	/// the contract having to call `set_code_hash` itself. The storage of the contract is kept.
+	///
+	/// The reference counts of both codes are adjusted, i.e. the old code is removable once no
+	/// other contract uses it. Returns the hash of the block in which the code was replaced.
+	#[method(name = "dev_setContractCode")]
+	async fn set_contract_code(&self, address: AccountId, code_hash: Hash) -> RpcResult<Hash>;
+}
+
+/// Implements the [`DevChainApiServer`] RPC trait.
+pub struct DevChain<C, P, BE> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+	deny_unsafe: DenyUnsafe,
+	_backend: PhantomData<BE>,
+}
+
+impl<C, P, BE> DevChain<C, P, BE>
+where
+	BE: sc_client_api::Backend<Block> + 'static,
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: Core<Block>
+		+ BlockBuilder<Block>
+		+ Metadata<Block>
+		+ AccountNonceApi<Block, AccountId, Index>,
+	P: TransactionPool<Block = Block> + 'static,
+{
+	/// Creates a new instance of the dev RPC methods.
+	///
+	/// Blocks are requested via `command_sink` if the chain is run with instant seal. Otherwise
+	/// the methods wait for the next block to be authored.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, pool, command_sink, deny_unsafe, _backend: PhantomData }
+	}
+
+	/// Reads the storage value `item` of `pallet` at `at`.
+	fn storage_value<T: Decode>(&self, at: Hash, pallet: &str, item: &str) -> RpcResult<Option<T>> {
+		let key = StorageKey([twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat());
+		self.client
+			.storage(at, &key)
+			.map_err(|e| error(format!("Unable to read {pallet}::{item}: {e}")))?
+			.map(|data| T::decode(&mut &data.0[..]))
+			.transpose()
+			.map_err(|e| error(format!("Unable to decode {pallet}::{item}: {e}")))
+	}
+
+	/// Dispatches `call` with root origin.
+	///
+	/// The call is wrapped into `Sudo::sudo_unchecked_weight`, as calls like `System::set_code`
+	/// declare the whole block as their weight. Returns the hash of the block which includes
+	/// the call once it was dispatched successfully.
+	pub async fn dispatch_as_root(&self, call: RuntimeCall) -> RpcResult<Hash> {
+		let best_hash = self.client.info().best_hash;
+		let sudo_key: AccountId = self
+			.storage_value(best_hash, "Sudo", "Key")?
+			.ok_or_else(|| error("The chain has no sudo key"))?;
+		let signer = Sr25519Keyring::iter()
+			.find(|keyring| keyring.to_account_id() == sudo_key)
+			.ok_or_else(|| error("The sudo key is not one of the development accounts"))?;
+
+		let call = RuntimeCall::Sudo(pallet_sudo::Call::sudo_unchecked_weight {
+			call: Box::new(call),
+			weight: Weight::zero(),
+		});
+		let xt = self.create_extrinsic(signer.pair(), call)?;
+		let block_hash = self.submit_and_watch(xt.clone()).await?;
+		self.ensure_dispatched(block_hash, &xt.encode())?;
+		Ok(block_hash)
+	}
+
+	/// Creates an extrinsic for `call` signed by `signer`, valid on top of the best block.
+	fn create_extrinsic(
+		&self,
+		signer: sr25519::Pair,
+		call: RuntimeCall,
+	) -> RpcResult<runtime::UncheckedExtrinsic> {
+		let best_hash = self.client.info().best_hash;
+		let account = AccountId::from(signer.public());
+		let nonce = self
+			.client
+			.runtime_api()
+			.account_nonce(best_hash, account.clone())
+			.map_err(|e| error(format!("Unable to query the nonce of {account}: {e}")))?;
+		extrinsic::create_extrinsic(&*self.client, signer, call, nonce).map_err(error)
+	}
+
+	/// Submits `xt` to the pool and waits until it is included in a block.
+	async fn submit_and_watch(&self, xt: runtime::UncheckedExtrinsic) -> RpcResult<Hash> {
+		let best_hash = self.client.info().best_hash;
+		let mut status = self
+			.pool
+			.submit_and_watch(best_hash, TransactionSource::Local, xt.into())
+			.await
+			.map_err(|e| error(format!("The transaction was rejected: {e}")))?;
+
+		while let Some(status) = status.next().await {
+			match status {
+				TransactionStatus::InBlock(block_hash) => return Ok(block_hash),
+				TransactionStatus::Future |
+				TransactionStatus::Ready |
+				TransactionStatus::Broadcast(_) => {},
+				status =>
+					return Err(error(format!("The transaction was not included: {status:?}"))),
+			}
+		}
+		Err(error("The transaction was not included"))
+	}
+
+	/// Returns an error if the extrinsic `xt` in the block `block_hash` failed, or if the call
+	/// it dispatched via `Sudo` failed.
+	fn ensure_dispatched(&self, block_hash: Hash, xt: &[u8]) -> RpcResult<()> {
+		let body = self
+			.client
+			.block_body(block_hash)
+			.map_err(|e| error(format!("Unable to read block {block_hash}: {e}")))?
+			.unwrap_or_default();
+		let index = body
+			.iter()
+			.position(|other| other.encode() == xt)
+			.ok_or_else(|| error(format!("The transaction is not part of block {block_hash}")))?;
+
+		let events: Vec<EventRecord<RuntimeEvent, Hash>> =
+			self.storage_value(block_hash, "System", "Events")?.unwrap_or_default();
+		let phase = Phase::ApplyExtrinsic(index as u32);
+		for record in events.into_iter().filter(|record| record.phase == phase) {
+			match record.event {
+				RuntimeEvent::System(frame_system::Event::ExtrinsicFailed {
+					dispatch_error,
+					..
+				}) => return Err(error(format!("The transaction failed: {dispatch_error:?}"))),
+				RuntimeEvent::Sudo(pallet_sudo::Event::Sudid { sudo_result: Err(e) }) =>
+					return Err(error(format!("The call failed: {e:?}"))),
+				_ => {},
+			}
+		}
+		Ok(())
+	}
+
+	/// Authors a new block if the chain is run with instant seal, otherwise waits for the next
+	/// block to be imported as the new best block.
+	pub async fn next_block(&self) -> RpcResult<Hash> {
+		let Some(mut command_sink) = self.command_sink.clone() else {
+			let mut imports = self.client.import_notification_stream();
+			while let Some(notification) = imports.next().await {
+				if notification.is_new_best {
+					return Ok(notification.hash)
+				}
+			}
+			return Err(error("The node stopped importing blocks"))
+		};
+
+		let (sender, receiver) = oneshot::channel();
+		command_sink
+			.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			})
+			.await
+			.map_err(|e| error(format!("Unable to request a new block: {e}")))?;
+		let block = receiver
+			.await
+			.map_err(|_| error("The block authorship task stopped"))?
+			.map_err(|e| error(format!("Unable to author a new block: {e}")))?;
+		Ok(block.hash)
+	}
+}
+
+#[async_trait]
+impl<C, P, BE> DevChainApiServer for DevChain<C, P, BE>
+where
+	BE: sc_client_api::Backend<Block> + 'static,
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, BE>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: Core<Block>
+		+ BlockBuilder<Block>
+		+ Metadata<Block>
+		+ AccountNonceApi<Block, AccountId, Index>,
+	P: TransactionPool<Block = Block> + 'static,
+{
+	async fn upgrade_runtime(&self, code: RuntimeCode) -> RpcResult<RuntimeVersion> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let code = code
+			.into_bytes()
+			.map_err(|e| error(format!("Unable to read the runtime code: {e}")))?;
+		self.dispatch_as_root(SystemCall::set_code { code }.into()).await?;
+
+		// The new runtime, and with it its migrations, is executed from the next block on.
+		let block_hash = self.next_block().await?;
+		self.client
+			.runtime_api()
+			.version(block_hash)
+			.map_err(|e| error(format!("Unable to query the runtime version: {e}")))
+	}
+
+	fn proof_size(&self, hash: Option<Hash>) -> RpcResult<BlockProofSize> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let hash = hash.unwrap_or_else(|| self.client.info().best_hash);
+		pov::measure(&*self.client, hash).map_err(error)
+	}
+
+	async fn set_random_seed(&self, seed: Option<Hash>) -> RpcResult<Hash> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.dispatch_as_root(DevRandomnessCall::set_seed { seed }.into()).await
+	}
+
+	async fn set_randomness(&self, randomness: Option<Hash>) -> RpcResult<Hash> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.dispatch_as_root(DevRandomnessCall::set_randomness { randomness }.into())
+			.await
+	}
+
+	fn state_diff(&self, from: Hash, to: Option<Hash>) -> RpcResult<StateDiff> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let to = to.unwrap_or_else(|| self.client.info().best_hash);
+		state_diff::diff(&*self.client, from, to).map_err(error)
+	}
+
+	async fn set_contract_code(&self, address: AccountId, code_hash: Hash) -> RpcResult<Hash> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.dispatch_as_root(ContractsCall::set_code { dest: address.into(), code_hash }.into())
+			.await
+	}
+}
@@ -1,16 +1,28 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use contracts_node_runtime::{self, opaque::Block, RuntimeApi};
+use futures::{channel::mpsc, StreamExt};
+use sc_client_api::BlockBackend;
+use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
+use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::EngineCommand;
 pub use sc_executor::NativeElseWasmExecutor;
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_service::{
+	error::Error as ServiceError, ChainType, Configuration, TaskManager, WarpSyncParams,
+};
 use sc_telemetry::{Telemetry, TelemetryWorker};
-use std::sync::Arc;
+use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
+use std::{sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
 
 impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
-	/// We only use the default Substrate host functions as runtime benchmarks are not supported.
+	/// Only enable the benchmarking host functions when we actually want to benchmark.
+	#[cfg(feature = "runtime-benchmarks")]
+	type ExtendHostFunctions = frame_benchmarking::benchmarking::HostFunctions;
+	/// Otherwise we only use the default Substrate host functions.
+	#[cfg(not(feature = "runtime-benchmarks"))]
 	type ExtendHostFunctions = ();
 
 	fn dispatch(method: &str, data: &[u8]) -> Option<Vec<u8>> {
@@ -26,6 +38,28 @@ pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullGrandpaBlockImport =
+	sc_consensus_grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
+type FullGrandpaLink = sc_consensus_grandpa::LinkHalf<Block, FullClient, FullSelectChain>;
+
+/// The consensus a chain is run with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Consensus {
+	/// A block is authored as soon as a transaction enters the pool.
+	InstantSeal,
+	/// Blocks are authored by Aura validators in fixed slots and finalized by Grandpa.
+	AuraGrandpa,
+}
+
+impl Consensus {
+	/// Development chains are run with instant seal, all others with Aura and Grandpa.
+	pub fn for_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+		match chain_spec.chain_type() {
+			ChainType::Development => Consensus::InstantSeal,
+			_ => Consensus::AuraGrandpa,
+		}
+	}
+}
 
 #[allow(clippy::type_complexity)]
 pub fn new_partial(
@@ -37,7 +71,7 @@ pub fn new_partial(
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block, FullClient>,
 		sc_transaction_pool::FullPool<Block, FullClient>,
-		(Option<Telemetry>,),
+		(Option<(FullGrandpaBlockImport, FullGrandpaLink)>, Option<Telemetry>),
 	>,
 	ServiceError,
 > {
@@ -69,11 +103,50 @@ pub fn new_partial(
 		telemetry
 	});
 
-	let import_queue = sc_consensus_manual_seal::import_queue(
-		Box::new(client.clone()),
-		&task_manager.spawn_essential_handle(),
-		config.prometheus_registry(),
-	);
+	let (import_queue, grandpa) = match Consensus::for_chain_spec(&*config.chain_spec) {
+		Consensus::InstantSeal => {
+			let import_queue = sc_consensus_manual_seal::import_queue(
+				Box::new(client.clone()),
+				&task_manager.spawn_essential_handle(),
+				config.prometheus_registry(),
+			);
+			(import_queue, None)
+		},
+		Consensus::AuraGrandpa => {
+			let (grandpa_block_import, grandpa_link) = sc_consensus_grandpa::block_import(
+				client.clone(),
+				&client,
+				select_chain.clone(),
+				telemetry.as_ref().map(|x| x.handle()),
+			)?;
+
+			let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+
+			let import_queue =
+				sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(ImportQueueParams {
+					block_import: grandpa_block_import.clone(),
+					justification_import: Some(Box::new(grandpa_block_import.clone())),
+					client: client.clone(),
+					create_inherent_data_providers: move |_, ()| async move {
+						let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+						let slot =
+							sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+								*timestamp,
+								slot_duration,
+							);
+
+						Ok((slot, timestamp))
+					},
+					spawner: &task_manager.spawn_essential_handle(),
+					registry: config.prometheus_registry(),
+					check_for_equivocation: Default::default(),
+					telemetry: telemetry.as_ref().map(|x| x.handle()),
+					compatibility_mode: Default::default(),
+				})?;
+			(import_queue, Some((grandpa_block_import, grandpa_link)))
+		},
+	};
 
 	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
 		config.transaction_pool.clone(),
@@ -91,12 +164,29 @@ pub fn new_partial(
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (telemetry,),
+		other: (grandpa, telemetry),
 	})
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// If `report_proof_size` is set, the proof size of every imported block is logged.
+pub fn new_full(
+	config: Configuration,
+	report_proof_size: bool,
+) -> Result<TaskManager, ServiceError> {
+	match Consensus::for_chain_spec(&*config.chain_spec) {
+		Consensus::InstantSeal => new_full_instant(config, report_proof_size),
+		Consensus::AuraGrandpa => new_full_aura(config, report_proof_size),
+	}
+}
+
+/// Builds a new service for a full client which authors a block as soon as a transaction enters
+/// the pool. Additional blocks are requested by the dev RPCs.
+fn new_full_instant(
+	config: Configuration,
+	report_proof_size: bool,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -105,11 +195,10 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		other: (mut telemetry,),
+		other: (_, mut telemetry),
 	} = new_partial(&config)?;
 
 	let net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
-
 	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
 			config: &config,
@@ -131,13 +220,20 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		);
 	}
 
+	// Allows the dev RPCs to request blocks.
+	let (command_sink, commands_stream) = mpsc::channel(1024);
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -158,6 +254,15 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if report_proof_size {
+		// Re-executing blocks is CPU-bound, so it runs on a blocking thread.
+		task_manager.spawn_handle().spawn_blocking(
+			"pov-report",
+			None,
+			crate::pov::report_proof_sizes(client.clone()),
+		);
+	}
+
 	let proposer = sc_basic_authorship::ProposerFactory::new(
 		task_manager.spawn_handle(),
 		client.clone(),
@@ -166,11 +271,22 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		telemetry.as_ref().map(|x| x.handle()),
 	);
 
-	let params = sc_consensus_manual_seal::InstantSealParams {
+	let pool_commands =
+		transaction_pool.pool().validated_pool().import_notification_stream().map(|_| {
+			EngineCommand::SealNewBlock {
+				create_empty: false,
+				finalize: false,
+				parent_hash: None,
+				sender: None,
+			}
+		});
+
+	let params = sc_consensus_manual_seal::ManualSealParams {
 		block_import: client.clone(),
 		env: proposer,
 		client,
 		pool: transaction_pool,
+		commands_stream: futures::stream::select(pool_commands, commands_stream),
 		select_chain,
 		consensus_data_provider: None,
 		create_inherent_data_providers: move |_, ()| async move {
@@ -178,7 +294,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		},
 	};
 
-	let authorship_future = sc_consensus_manual_seal::run_instant_seal(params);
+	let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 
 	task_manager
 		.spawn_essential_handle()
@@ -187,3 +303,195 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+/// Builds a new service for a full client which authors blocks with Aura and finalizes them
+/// with Grandpa.
+fn new_full_aura(
+	config: Configuration,
+	report_proof_size: bool,
+) -> Result<TaskManager, ServiceError> {
+	let sc_service::PartialComponents {
+		client,
+		backend,
+		mut task_manager,
+		import_queue,
+		keystore_container,
+		select_chain,
+		transaction_pool,
+		other: (grandpa, mut telemetry),
+	} = new_partial(&config)?;
+	let (block_import, grandpa_link) =
+		grandpa.ok_or_else(|| ServiceError::Other("Grandpa is not set up".into()))?;
+
+	let grandpa_protocol_name = sc_consensus_grandpa::protocol_standard_name(
+		&client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
+		&config.chain_spec,
+	);
+
+	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
+	net_config.add_notification_protocol(sc_consensus_grandpa::grandpa_peers_set_config(
+		grandpa_protocol_name.clone(),
+	));
+	let warp_sync = Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
+		backend.clone(),
+		grandpa_link.shared_authority_set().clone(),
+		Vec::default(),
+	));
+
+	let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			net_config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue,
+			block_announce_validator_builder: None,
+			warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
+		})?;
+
+	if config.offchain_worker.enabled {
+		sc_service::build_offchain_workers(
+			&config,
+			task_manager.spawn_handle(),
+			client.clone(),
+			network.clone(),
+		);
+	}
+
+	let role = config.role.clone();
+	let force_authoring = config.force_authoring;
+	let name = config.network.node_name.clone();
+	let enable_grandpa = !config.disable_grandpa;
+
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: None,
+			};
+			crate::rpc::create_full(deps).map_err(Into::into)
+		})
+	};
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		network: network.clone(),
+		client: client.clone(),
+		keystore: keystore_container.keystore(),
+		task_manager: &mut task_manager,
+		transaction_pool: transaction_pool.clone(),
+		rpc_builder: rpc_extensions_builder,
+		backend,
+		system_rpc_tx,
+		tx_handler_controller,
+		sync_service: sync_service.clone(),
+		config,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	if report_proof_size {
+		// Re-executing blocks is CPU-bound, so it runs on a blocking thread.
+		task_manager.spawn_handle().spawn_blocking(
+			"pov-report",
+			None,
+			crate::pov::report_proof_sizes(client.clone()),
+		);
+	}
+
+	if role.is_authority() {
+		let proposer = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+
+		let aura = sc_consensus_aura::start_aura::<AuraPair, _, _, _, _, _, _, _, _, _, _>(
+			StartAuraParams {
+				slot_duration,
+				client,
+				select_chain,
+				block_import,
+				proposer_factory: proposer,
+				create_inherent_data_providers: move |_, ()| async move {
+					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+					let slot =
+						sp_consensus_aura::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+							*timestamp,
+							slot_duration,
+						);
+
+					Ok((slot, timestamp))
+				},
+				force_authoring,
+				backoff_authoring_blocks: Option::<()>::None,
+				keystore: keystore_container.keystore(),
+				sync_oracle: sync_service.clone(),
+				justification_sync_link: sync_service.clone(),
+				block_proposal_slot_portion: SlotProportion::new(2f32 / 3f32),
+				max_block_proposal_slot_portion: None,
+				telemetry: telemetry.as_ref().map(|x| x.handle()),
+				compatibility_mode: Default::default(),
+			},
+		)?;
+
+		// the AURA authoring task is considered essential, i.e. if it
+		// fails we take down the service with it.
+		task_manager
+			.spawn_essential_handle()
+			.spawn_blocking("aura", Some("block-authoring"), aura);
+	}
+
+	if enable_grandpa {
+		// if the node isn't actively participating in consensus then it doesn't
+		// need a keystore, regardless of which protocol we use below.
+		let keystore = if role.is_authority() { Some(keystore_container.keystore()) } else { None };
+
+		let grandpa_config = sc_consensus_grandpa::Config {
+			gossip_duration: Duration::from_millis(333),
+			justification_period: 512,
+			name: Some(name),
+			observer_enabled: false,
+			keystore,
+			local_role: role,
+			telemetry: telemetry.as_ref().map(|x| x.handle()),
+			protocol_name: grandpa_protocol_name,
+		};
+
+		// start the full GRANDPA voter
+		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
+		// this point the full voter should provide better guarantees of block
+		// and vote data availability than the observer.
+		let grandpa_config = sc_consensus_grandpa::GrandpaParams {
+			config: grandpa_config,
+			link: grandpa_link,
+			network,
+			sync: Arc::new(sync_service),
+			voting_rule: sc_consensus_grandpa::VotingRulesBuilder::default().build(),
+			prometheus_registry,
+			shared_voter_state: SharedVoterState::empty(),
+			telemetry: telemetry.as_ref().map(|x| x.handle()),
+		};
+
+		// the GRANDPA voter task is considered infallible, i.e.
+		// if it fails we take down the service with it.
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"grandpa-voter",
+			None,
+			sc_consensus_grandpa::run_grandpa_voter(grandpa_config)?,
+		);
+	}
+
+	network_starter.start_network();
+	Ok(task_manager)
+}
@@ -0,0 +1,321 @@
+//! Computes the changes to the state between two blocks.
+//!
+//! Changed top-level keys are annotated with the pallet and storage item they belong to, as
+//! declared in the runtime metadata at the later block. The storage of contracts lives in child
+//! tries, whose changes are reported per contract.
+
+use crate::rpc::contracts::{map_prefix, ContractInfo};
+use codec::Decode;
+use contracts_node_runtime::{opaque::Block, AccountId, Hash};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use sc_client_api::StorageProvider;
+use serde::{Deserialize, Serialize};
+use sp_api::{Metadata, ProvideRuntimeApi};
+use sp_core::{
+	hashing::twox_128,
+	storage::{well_known_keys::CHILD_STORAGE_KEY_PREFIX, ChildInfo, StorageKey},
+	Bytes,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The changes to the state between two blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiff {
+	/// The changed top-level keys, except for the roots of child tries.
+	pub top: Vec<TopStorageChange>,
+	/// The changed storage of contracts.
+	pub contracts: Vec<ContractStorageChanges>,
+}
+
+/// A changed top-level key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopStorageChange {
+	/// The pallet the key belongs to, if it is known from the metadata.
+	pub pallet: Option<String>,
+	/// The storage item the key belongs to, if it is known from the metadata.
+	pub item: Option<String>,
+	/// The change of the key.
+	#[serde(flatten)]
+	pub change: StorageChange,
+}
+
+/// The changed keys in the storage of a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractStorageChanges {
+	/// The address of the contract.
+	pub address: AccountId,
+	/// The changes of the keys in the child trie of the contract.
+	pub changes: Vec<StorageChange>,
+}
+
+/// A changed key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageChange {
+	/// The key.
+	pub key: Bytes,
+	/// The value at the earlier block, `None` if the key was added.
+	pub before: Option<Bytes>,
+	/// The value at the later block, `None` if the key was removed.
+	pub after: Option<Bytes>,
+}
+
+/// Returns the changes to the state between the blocks `from` and `to`.
+pub fn diff<C, BE>(client: &C, from: Hash, to: Hash) -> Result<StateDiff, String>
+where
+	BE: sc_client_api::Backend<Block>,
+	C: ProvideRuntimeApi<Block> + StorageProvider<Block, BE>,
+	C::Api: Metadata<Block>,
+{
+	let (top_before, top_after) = (top_pairs(client, from)?, top_pairs(client, to)?);
+
+	let contracts = contract_changes(
+		(&top_before, &top_after),
+		(&contracts(client, from)?, &contracts(client, to)?),
+		|trie| child_pairs(client, from, trie),
+		|trie| child_pairs(client, to, trie),
+	)?;
+
+	let items = storage_items(client, to)?;
+	let is_child_root = |key: &[u8]| key.starts_with(CHILD_STORAGE_KEY_PREFIX);
+	let top = changes(top_before, top_after)
+		.into_iter()
+		.filter(|change| !is_child_root(&change.key))
+		.map(|change| {
+			let (pallet, item) =
+				change.key.get(..32).and_then(|prefix| items.get(prefix)).cloned().unzip();
+			TopStorageChange { pallet, item, change }
+		})
+		.collect();
+
+	Ok(StateDiff { top, contracts })
+}
+
+/// Returns the changes to the storage of contracts, given the top-level pairs and the trie IDs
+/// of the contracts before and after.
+///
+/// The child tries are read via `read_before` and `read_after`, but only those of contracts
+/// whose trie ID or child trie root changed.
+fn contract_changes(
+	(top_before, top_after): (&BTreeMap<Vec<u8>, Vec<u8>>, &BTreeMap<Vec<u8>, Vec<u8>>),
+	(before, after): (&BTreeMap<AccountId, Vec<u8>>, &BTreeMap<AccountId, Vec<u8>>),
+	read_before: impl Fn(&[u8]) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String>,
+	read_after: impl Fn(&[u8]) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String>,
+) -> Result<Vec<ContractStorageChanges>, String> {
+	let mut contracts = Vec::new();
+	for address in before.keys().chain(after.keys()).collect::<BTreeSet<_>>() {
+		let (trie_before, trie_after) = (before.get(address), after.get(address));
+		// Skip child tries whose root didn't change.
+		if let (Some(trie_before), Some(trie_after)) = (trie_before, trie_after) {
+			let root = ChildInfo::new_default(trie_after).prefixed_storage_key().into_inner();
+			if trie_before == trie_after && top_before.get(&root) == top_after.get(&root) {
+				continue
+			}
+		}
+		let pairs_before = trie_before.map(|trie| read_before(trie)).transpose()?;
+		let pairs_after = trie_after.map(|trie| read_after(trie)).transpose()?;
+		let changes = changes(pairs_before.unwrap_or_default(), pairs_after.unwrap_or_default());
+		if !changes.is_empty() {
+			contracts.push(ContractStorageChanges { address: address.clone(), changes });
+		}
+	}
+	Ok(contracts)
+}
+
+/// Returns the changes between the key-value pairs `before` and `after`.
+fn changes(
+	mut before: BTreeMap<Vec<u8>, Vec<u8>>,
+	after: BTreeMap<Vec<u8>, Vec<u8>>,
+) -> Vec<StorageChange> {
+	let mut changes = Vec::new();
+	for (key, value) in after {
+		match before.remove(&key) {
+			Some(old) if old == value => {},
+			old => changes.push(StorageChange {
+				key: key.into(),
+				before: old.map(Into::into),
+				after: Some(value.into()),
+			}),
+		}
+	}
+	changes.extend(before.into_iter().map(|(key, old)| StorageChange {
+		key: key.into(),
+		before: Some(old.into()),
+		after: None,
+	}));
+	changes.sort_by(|a, b| a.key.cmp(&b.key));
+	changes
+}
+
+/// Returns the pallet and name of the storage items declared in the metadata at `at`, by the
+/// prefix of their keys.
+fn storage_items<C>(client: &C, at: Hash) -> Result<HashMap<Vec<u8>, (String, String)>, String>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: Metadata<Block>,
+{
+	let metadata = client
+		.runtime_api()
+		.metadata(at)
+		.map_err(|e| format!("Unable to query the metadata: {e}"))?;
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+		.map_err(|e| format!("Unable to decode the metadata: {e}"))?;
+	let RuntimeMetadata::V14(metadata) = metadata.1 else {
+		return Err("Unsupported metadata version".into())
+	};
+
+	let mut items = HashMap::new();
+	for storage in metadata.pallets.into_iter().filter_map(|pallet| pallet.storage) {
+		for entry in storage.entries {
+			let prefix = [twox_128(storage.prefix.as_bytes()), twox_128(entry.name.as_bytes())];
+			items.insert(prefix.concat(), (storage.prefix.clone(), entry.name));
+		}
+	}
+	Ok(items)
+}
+
+/// Returns all top-level key-value pairs at `at`.
+fn top_pairs<C, BE>(client: &C, at: Hash) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String>
+where
+	BE: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	Ok(client
+		.storage_pairs(at, None, None)
+		.map_err(|e| format!("Unable to read the state at {at}: {e}"))?
+		.map(|(key, value)| (key.0, value.0))
+		.collect())
+}
+
+/// Returns the trie IDs of the contracts at `at`, by their address.
+fn contracts<C, BE>(client: &C, at: Hash) -> Result<BTreeMap<AccountId, Vec<u8>>, String>
+where
+	BE: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	let prefix = map_prefix("ContractInfoOf");
+	client
+		.storage_pairs(at, Some(&prefix), None)
+		.map_err(|e| format!("Unable to read the contracts at {at}: {e}"))?
+		.map(|(key, value)| {
+			// `ContractInfoOf` uses the `Twox64Concat` hasher.
+			let address = AccountId::decode(&mut &key.0[prefix.0.len() + 8..]);
+			let info = ContractInfo::decode(&mut &value.0[..]);
+			match (address, info) {
+				(Ok(address), Ok(info)) => Ok((address, info.trie_id)),
+				_ => Err(format!("Unable to decode the contract at {key:?}")),
+			}
+		})
+		.collect()
+}
+
+/// Returns all key-value pairs in the child trie `trie_id` at `at`.
+fn child_pairs<C, BE>(
+	client: &C,
+	at: Hash,
+	trie_id: &[u8],
+) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, String>
+where
+	BE: sc_client_api::Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	let child_info = ChildInfo::new_default(trie_id);
+	client
+		.child_storage_keys(at, child_info.clone(), None, None)
+		.map_err(|e| format!("Unable to read the child trie at {at}: {e}"))?
+		.map(|key: StorageKey| {
+			let value = client
+				.child_storage(at, &child_info, &key)
+				.map_err(|e| format!("Unable to read the child trie at {at}: {e}"))?
+				.unwrap_or_default();
+			Ok((key.0, value.0))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+
+	fn pairs(pairs: &[(&str, &str)]) -> BTreeMap<Vec<u8>, Vec<u8>> {
+		pairs
+			.iter()
+			.map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+			.collect()
+	}
+
+	fn change(key: &str, before: Option<&str>, after: Option<&str>) -> StorageChange {
+		let bytes = |value: &str| value.as_bytes().to_vec().into();
+		StorageChange { key: bytes(key), before: before.map(bytes), after: after.map(bytes) }
+	}
+
+	fn root_key(trie_id: &[u8]) -> Vec<u8> {
+		ChildInfo::new_default(trie_id).prefixed_storage_key().into_inner()
+	}
+
+	#[test]
+	fn reports_added_removed_and_modified_keys() {
+		let before = pairs(&[("modified", "1"), ("removed", "2"), ("unchanged", "3")]);
+		let after = pairs(&[("added", "4"), ("modified", "5"), ("unchanged", "3")]);
+
+		assert_eq!(
+			changes(before, after),
+			vec![
+				change("added", None, Some("4")),
+				change("modified", Some("1"), Some("5")),
+				change("removed", Some("2"), None),
+			]
+		);
+	}
+
+	#[test]
+	fn reports_nothing_for_unchanged_keys() {
+		let state = pairs(&[("a", "1"), ("b", "2")]);
+		assert_eq!(changes(state.clone(), state), vec![]);
+	}
+
+	#[test]
+	fn reads_the_child_tries_of_changed_contracts_only() {
+		let (unchanged, changed) = (AccountId::new([1; 32]), AccountId::new([2; 32]));
+		let contracts: BTreeMap<_, _> =
+			[(unchanged.clone(), b"unchanged".to_vec()), (changed.clone(), b"changed".to_vec())]
+				.into();
+		let top = |changed_root: &[u8]| {
+			BTreeMap::from([
+				(root_key(b"unchanged"), b"root".to_vec()),
+				(root_key(b"changed"), changed_root.to_vec()),
+			])
+		};
+		let (top_before, top_after) = (top(b"before"), top(b"after"));
+
+		let read = RefCell::new(Vec::new());
+		// Records which child tries are read.
+		let read_child = |value: &'static str| {
+			let read = &read;
+			move |trie: &[u8]| {
+				read.borrow_mut().push(trie.to_vec());
+				Ok::<_, String>(pairs(&[("key", value)]))
+			}
+		};
+		let contracts = contract_changes(
+			(&top_before, &top_after),
+			(&contracts, &contracts),
+			read_child("before"),
+			read_child("after"),
+		)
+		.unwrap();
+
+		assert_eq!(
+			contracts,
+			vec![ContractStorageChanges {
+				address: changed,
+				changes: vec![change("key", Some("before"), Some("after"))],
+			}]
+		);
+		assert_eq!(read.into_inner(), vec![b"changed".to_vec(), b"changed".to_vec()]);
+	}
+}
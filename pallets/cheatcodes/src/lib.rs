@@ -0,0 +1,285 @@
+//! # Cheatcodes Pallet
+//!
+//! Backs the cheatcodes chain extension, which lets test contracts manipulate their
+//! environment, similar to the cheatcodes of Foundry:
+//!
+//! - [`Pallet::set_next_timestamp`] overrides the timestamp contracts observe in the next block.
+//!   The override is applied via [`OverridableTime`], which the runtime uses as
+//!   `pallet_contracts::Config::Time`. Other pallets keep observing the real timestamp.
+//! - [`Pallet::set_balance`] sets the free balance of an account.
+//! - [`Pallet::impersonate`] makes the next call a contract dispatches via
+//!   [`Pallet::dispatch_impersonated`] come from another account.
+//! - [`Pallet::snapshot`] and [`Pallet::revert`] save and restore the storage of a contract.
+//!
+//! Impersonation only applies to runtime calls, which contracts dispatch via `call_runtime`.
+//! Contracts call other contracts with `seal_call` and `seal_delegate_call` only, always as
+//! themselves: a call made from within a chain extension or `call_runtime` would bypass the
+//! reentrancy protection and storage accounting of the call stack.
+//!
+//! None of this is safe to use on a production chain. The runtime only includes the pallet if
+//! it is built with the `cheatcodes` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	storage::{child, unhashed, with_transaction},
+	traits::{Currency, Get, PalletInfoAccess, Time},
+	BoundedVec, StorageHasher, Twox64Concat,
+};
+use sp_runtime::{DispatchError, TransactionOutcome};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The moment type of the underlying timestamp.
+pub type MomentOf<T> = <<T as Config>::Timestamp as Time>::Moment;
+/// The balance type of `pallet_contracts`.
+pub type BalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+/// The storage of a contract saved in a snapshot.
+pub type SnapshotOf<T> = BoundedVec<
+	(
+		BoundedVec<u8, <T as Config>::MaxSnapshotKeyLen>,
+		BoundedVec<u8, <T as Config>::MaxSnapshotValueLen>,
+	),
+	<T as Config>::MaxSnapshotItems,
+>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Dispatchable;
+	use sp_std::boxed::Box;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_contracts::Config {
+		/// The overarching call type, of the calls dispatched as an impersonated account.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo;
+
+		/// The source of the real timestamp, which [`OverridableTime`] falls back to.
+		type Timestamp: Time;
+
+		/// The maximum number of storage items of a contract a snapshot can save.
+		#[pallet::constant]
+		type MaxSnapshotItems: Get<u32>;
+
+		/// The maximum length of a key in the child trie of a contract, in bytes.
+		#[pallet::constant]
+		type MaxSnapshotKeyLen: Get<u32>;
+
+		/// The maximum length of a value in the child trie of a contract, in bytes.
+		#[pallet::constant]
+		type MaxSnapshotValueLen: Get<u32>;
+	}
+
+	/// The timestamp contracts observe in the next block.
+	#[pallet::storage]
+	pub type NextTimestamp<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
+
+	/// The timestamp contracts observe in the current block.
+	#[pallet::storage]
+	pub type TimestampOverride<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
+
+	/// The account each contract impersonates in its next call to
+	/// [`Pallet::dispatch_impersonated`].
+	#[pallet::storage]
+	pub type Impersonations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// The snapshots of contract storage, by snapshot ID.
+	#[pallet::storage]
+	pub type Snapshots<T: Config> =
+		StorageMap<_, Twox64Concat, u32, (T::AccountId, SnapshotOf<T>), OptionQuery>;
+
+	/// The ID of the next snapshot.
+	#[pallet::storage]
+	pub type NextSnapshotId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			match NextTimestamp::<T>::take() {
+				Some(timestamp) => {
+					TimestampOverride::<T>::put(timestamp);
+					// Includes removing the override in `on_finalize`.
+					T::DbWeight::get().reads_writes(2, 3)
+				},
+				None => T::DbWeight::get().reads(2),
+			}
+		}
+
+		fn on_finalize(_n: BlockNumberFor<T>) {
+			if TimestampOverride::<T>::exists() {
+				TimestampOverride::<T>::kill();
+			}
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The contract doesn't exist.
+		ContractNotFound,
+		/// The snapshot doesn't exist or belongs to another contract.
+		SnapshotNotFound,
+		/// The storage of the contract exceeds the bounds of a snapshot.
+		SnapshotTooLarge,
+		/// The signer doesn't impersonate an account.
+		NotImpersonating,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Dispatches `call` as the account the signer impersonates.
+		///
+		/// Contracts impersonate an account via the cheatcodes chain extension and then
+		/// dispatch this call via `call_runtime`. The impersonation only applies to this one
+		/// call, whether it succeeds or not.
+		#[pallet::call_index(0)]
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			(
+				dispatch_info.weight.saturating_add(T::DbWeight::get().reads_writes(1, 1)),
+				dispatch_info.class,
+			)
+		})]
+		pub fn dispatch_impersonated(
+			origin: OriginFor<T>,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let impersonated =
+				Impersonations::<T>::take(&who).ok_or(Error::<T>::NotImpersonating)?;
+			call.dispatch(frame_system::RawOrigin::Signed(impersonated).into())
+				.map(|_| ())
+				.map_err(|e| e.error)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Makes contracts observe `timestamp` in the next block.
+	pub fn set_next_timestamp(timestamp: MomentOf<T>) {
+		NextTimestamp::<T>::put(timestamp);
+	}
+
+	/// Sets the free balance of `who` to `amount`.
+	pub fn set_balance(who: &T::AccountId, amount: BalanceOf<T>) {
+		let _ = <T as pallet_contracts::Config>::Currency::make_free_balance_be(who, amount);
+	}
+
+	/// Makes the next call `contract` dispatches via [`Pallet::dispatch_impersonated`] come
+	/// from `who`.
+	pub fn impersonate(contract: T::AccountId, who: T::AccountId) {
+		Impersonations::<T>::insert(contract, who);
+	}
+
+	/// Saves the storage of `contract` and returns the ID of the snapshot and the number of
+	/// storage items it saved.
+	///
+	/// Only the storage of `contract` is saved, not its balance or the state of other accounts.
+	pub fn snapshot(contract: T::AccountId) -> Result<(u32, u32), DispatchError> {
+		let child_info = Self::child_info(&contract).ok_or(Error::<T>::ContractNotFound)?;
+		let pairs = Self::child_pairs(&child_info).ok_or(Error::<T>::SnapshotTooLarge)?;
+		let id = NextSnapshotId::<T>::mutate(|next| {
+			let id = *next;
+			*next = next.wrapping_add(1);
+			id
+		});
+		let len = pairs.len() as u32;
+		Snapshots::<T>::insert(id, (contract, pairs));
+		Ok((id, len))
+	}
+
+	/// Restores the storage of `contract` from the snapshot `id` and returns the number of
+	/// storage items it removed and restored.
+	///
+	/// The snapshot is kept, so a contract can be reverted to it repeatedly. The storage
+	/// deposit of the contract is not rewound, i.e. its accounting of storage items and bytes
+	/// diverges from its actual storage. At most [`Config::MaxSnapshotItems`] items are removed
+	/// first: if the contract has more, its storage is left unchanged.
+	pub fn revert(contract: T::AccountId, id: u32) -> Result<(u32, u32), DispatchError> {
+		let child_info = Self::child_info(&contract).ok_or(Error::<T>::ContractNotFound)?;
+		let pairs = Snapshots::<T>::get(id)
+			.filter(|(owner, _)| *owner == contract)
+			.map(|(_, pairs)| pairs)
+			.ok_or(Error::<T>::SnapshotNotFound)?;
+
+		with_transaction(|| {
+			let removal = child::clear_storage(&child_info, Some(T::MaxSnapshotItems::get()), None);
+			if removal.maybe_cursor.is_some() {
+				return TransactionOutcome::Rollback(Err(Error::<T>::SnapshotTooLarge.into()))
+			}
+			let restored = pairs.len() as u32;
+			for (key, value) in pairs {
+				child::put_raw(&child_info, &key, &value);
+			}
+			TransactionOutcome::Commit(Ok((removal.unique, restored)))
+		})
+	}
+
+	/// Returns the child trie holding the storage of `contract`.
+	///
+	/// `pallet_contracts` doesn't expose the trie ID of contracts, so it is read from the raw
+	/// `ContractInfoOf` storage, in which the trie ID is the first field.
+	fn child_info(contract: &T::AccountId) -> Option<child::ChildInfo> {
+		let mut key = frame_support::storage::storage_prefix(
+			<pallet_contracts::Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+			b"ContractInfoOf",
+		)
+		.to_vec();
+		key.extend(Twox64Concat::hash(&contract.encode()));
+		let raw = unhashed::get_raw(&key)?;
+		let trie_id = Vec::<u8>::decode(&mut &raw[..]).ok()?;
+		Some(child::ChildInfo::new_default(&trie_id))
+	}
+
+	/// Returns all key-value pairs of the child trie `child_info`, or `None` if they exceed the
+	/// bounds of a snapshot.
+	fn child_pairs(child_info: &child::ChildInfo) -> Option<SnapshotOf<T>> {
+		let mut pairs = SnapshotOf::<T>::default();
+		let mut key = Vec::new();
+		while let Some(next) =
+			sp_io::default_child_storage::next_key(child_info.storage_key(), &key)
+		{
+			if let Some(value) = child::get_raw(child_info, &next) {
+				let pair = (next.clone().try_into().ok()?, value.try_into().ok()?);
+				pairs.try_push(pair).ok()?;
+			}
+			key = next;
+		}
+		Some(pairs)
+	}
+}
+
+/// The timestamp as observed by contracts, for `pallet_contracts::Config::Time`.
+///
+/// Returns the timestamp set via [`Pallet::set_next_timestamp`] in the block after it was set,
+/// otherwise the one of [`Config::Timestamp`].
+pub struct OverridableTime<T>(PhantomData<T>);
+
+impl<T: Config> Time for OverridableTime<T> {
+	type Moment = MomentOf<T>;
+
+	fn now() -> Self::Moment {
+		TimestampOverride::<T>::get().unwrap_or_else(T::Timestamp::now)
+	}
+}
@@ -0,0 +1,153 @@
+use crate::{mock::*, Error, Impersonations, OverridableTime, Snapshots};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::{child, storage_prefix, unhashed},
+	traits::{Hooks, Time},
+	StorageHasher, Twox64Concat,
+};
+use sp_runtime::AccountId32;
+
+/// Registers `contract` with `pallet_contracts`, as far as the pallet is concerned: its
+/// `ContractInfo` starts with the ID of its child trie.
+fn register_contract(contract: &AccountId32, trie_id: &[u8]) -> child::ChildInfo {
+	let mut key = storage_prefix(b"Contracts", b"ContractInfoOf").to_vec();
+	key.extend(Twox64Concat::hash(&contract.encode()));
+	unhashed::put_raw(&key, &trie_id.to_vec().encode());
+	child::ChildInfo::new_default(trie_id)
+}
+
+#[test]
+fn timestamp_is_overridden_in_the_next_block_only() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		Cheatcodes::set_next_timestamp(42);
+		assert_eq!(OverridableTime::<Test>::now(), 1_000);
+
+		Cheatcodes::on_initialize(2);
+		assert_eq!(OverridableTime::<Test>::now(), 42);
+		// Other pallets keep observing the real timestamp.
+		assert_eq!(Timestamp::now(), 1_000);
+
+		Cheatcodes::on_finalize(2);
+		Cheatcodes::on_initialize(3);
+		assert_eq!(OverridableTime::<Test>::now(), 1_000);
+	});
+}
+
+#[test]
+fn set_balance_works() {
+	new_test_ext().execute_with(|| {
+		Cheatcodes::set_balance(&ALICE, 100);
+		assert_eq!(Balances::free_balance(&ALICE), 100);
+		Cheatcodes::set_balance(&ALICE, 10);
+		assert_eq!(Balances::free_balance(&ALICE), 10);
+	});
+}
+
+#[test]
+fn impersonation_applies_to_the_next_call_only() {
+	new_test_ext().execute_with(|| {
+		Cheatcodes::set_balance(&BOB, 100);
+		let transfer = || {
+			Box::new(RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+				dest: CHARLIE,
+				value: 10,
+			}))
+		};
+
+		Cheatcodes::impersonate(ALICE, BOB);
+		assert_ok!(Cheatcodes::dispatch_impersonated(RuntimeOrigin::signed(ALICE), transfer()));
+		assert_eq!(Balances::free_balance(&BOB), 90);
+		assert_eq!(Balances::free_balance(&CHARLIE), 10);
+		assert!(!Impersonations::<Test>::contains_key(&ALICE));
+
+		assert_noop!(
+			Cheatcodes::dispatch_impersonated(RuntimeOrigin::signed(ALICE), transfer()),
+			Error::<Test>::NotImpersonating
+		);
+		// Only the contract which impersonates an account dispatches calls as it.
+		Cheatcodes::impersonate(ALICE, BOB);
+		assert_noop!(
+			Cheatcodes::dispatch_impersonated(RuntimeOrigin::signed(CHARLIE), transfer()),
+			Error::<Test>::NotImpersonating
+		);
+	});
+}
+
+#[test]
+fn snapshot_and_revert_work() {
+	new_test_ext().execute_with(|| {
+		let child_info = register_contract(&ALICE, b"alice");
+		child::put_raw(&child_info, b"a", b"1");
+
+		let (id, len) = Cheatcodes::snapshot(ALICE).unwrap();
+		assert_eq!(len, 1);
+
+		child::put_raw(&child_info, b"a", b"2");
+		child::put_raw(&child_info, b"b", b"3");
+		assert_eq!(Cheatcodes::revert(ALICE, id), Ok((2, 1)));
+		assert_eq!(child::get_raw(&child_info, b"a"), Some(b"1".to_vec()));
+		assert_eq!(child::get_raw(&child_info, b"b"), None);
+
+		// The snapshot is kept.
+		child::put_raw(&child_info, b"a", b"4");
+		assert_ok!(Cheatcodes::revert(ALICE, id));
+		assert_eq!(child::get_raw(&child_info, b"a"), Some(b"1".to_vec()));
+	});
+}
+
+#[test]
+fn snapshots_belong_to_their_contract() {
+	new_test_ext().execute_with(|| {
+		register_contract(&ALICE, b"alice");
+		register_contract(&BOB, b"bob");
+		let (id, _) = Cheatcodes::snapshot(ALICE).unwrap();
+
+		assert_noop!(Cheatcodes::revert(BOB, id), Error::<Test>::SnapshotNotFound);
+		assert_noop!(Cheatcodes::revert(ALICE, id + 1), Error::<Test>::SnapshotNotFound);
+	});
+}
+
+#[test]
+fn snapshot_requires_a_contract() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Cheatcodes::snapshot(ALICE), Error::<Test>::ContractNotFound);
+	});
+}
+
+#[test]
+fn snapshot_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let child_info = register_contract(&ALICE, b"alice");
+		child::put_raw(&child_info, b"a", b"1");
+		child::put_raw(&child_info, b"b", b"2");
+		child::put_raw(&child_info, b"c", b"3");
+		assert_noop!(Cheatcodes::snapshot(ALICE), Error::<Test>::SnapshotTooLarge);
+
+		child::kill(&child_info, b"c");
+		child::put_raw(&child_info, b"b", b"too long!");
+		assert_noop!(Cheatcodes::snapshot(ALICE), Error::<Test>::SnapshotTooLarge);
+
+		child::put_raw(&child_info, b"b", b"2");
+		assert_ok!(Cheatcodes::snapshot(ALICE));
+		assert_eq!(Snapshots::<Test>::iter().count(), 1);
+	});
+}
+
+#[test]
+fn revert_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let child_info = register_contract(&ALICE, b"alice");
+		child::put_raw(&child_info, b"a", b"1");
+		let (id, _) = Cheatcodes::snapshot(ALICE).unwrap();
+
+		child::put_raw(&child_info, b"b", b"2");
+		child::put_raw(&child_info, b"c", b"3");
+		assert_noop!(Cheatcodes::revert(ALICE, id), Error::<Test>::SnapshotTooLarge);
+
+		child::kill(&child_info, b"c");
+		assert_eq!(Cheatcodes::revert(ALICE, id), Ok((2, 1)));
+		assert_eq!(child::get_raw(&child_info, b"b"), None);
+	});
+}
@@ -0,0 +1,124 @@
+//! # Contracts Call Filter Pallet
+//!
+//! Holds an allowlist of the runtime calls contracts may dispatch via `call_runtime`, so
+//! contracts dispatching runtime calls can be tested without changing the runtime. The list is
+//! seeded from the chain spec and can be edited by root via [`Pallet::allow_call`] and
+//! [`Pallet::disallow_call`].
+//!
+//! Calls are identified by the index of their pallet in `construct_runtime!` and their call
+//! index within the pallet, i.e. the first two bytes of an encoded `RuntimeCall`. The runtime
+//! uses [`AllowedCallFilter`] as `pallet_contracts::Config::CallFilter`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use frame_support::traits::Contains;
+use sp_std::marker::PhantomData;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Returns the pallet and call index of `call`, i.e. the first two bytes of its encoding.
+pub fn call_index(call: &impl Encode) -> Option<(u8, u8)> {
+	call.using_encoded(|encoded| match encoded {
+		[pallet_index, call_index, ..] => Some((*pallet_index, *call_index)),
+		_ => None,
+	})
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	/// The calls contracts may dispatch, by pallet and call index.
+	#[pallet::storage]
+	pub type AllowedCalls<T: Config> = StorageMap<_, Twox64Concat, (u8, u8), (), OptionQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub allowed_calls: Vec<(u8, u8)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for call in &self.allowed_calls {
+				AllowedCalls::<T>::insert(call, ());
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Contracts may dispatch the call.
+		CallAllowed { pallet_index: u8, call_index: u8 },
+		/// Contracts may no longer dispatch the call.
+		CallDisallowed { pallet_index: u8, call_index: u8 },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allows contracts to dispatch the call `call_index` of the pallet `pallet_index`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn allow_call(
+			origin: OriginFor<T>,
+			pallet_index: u8,
+			call_index: u8,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			AllowedCalls::<T>::insert((pallet_index, call_index), ());
+			Self::deposit_event(Event::CallAllowed { pallet_index, call_index });
+			Ok(())
+		}
+
+		/// Disallows contracts to dispatch the call `call_index` of the pallet `pallet_index`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn disallow_call(
+			origin: OriginFor<T>,
+			pallet_index: u8,
+			call_index: u8,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			AllowedCalls::<T>::remove((pallet_index, call_index));
+			Self::deposit_event(Event::CallDisallowed { pallet_index, call_index });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether contracts may dispatch the call `call_index` of the pallet `pallet_index`.
+		pub fn is_allowed(pallet_index: u8, call_index: u8) -> bool {
+			AllowedCalls::<T>::contains_key((pallet_index, call_index))
+		}
+	}
+}
+
+/// Allows the calls in [`AllowedCalls`], for `pallet_contracts::Config::CallFilter`.
+pub struct AllowedCallFilter<T>(PhantomData<T>);
+
+impl<T: Config, Call: Encode> Contains<Call> for AllowedCallFilter<T> {
+	fn contains(call: &Call) -> bool {
+		call_index(call).map_or(false, |(pallet_index, call_index)| {
+			Pallet::<T>::is_allowed(pallet_index, call_index)
+		})
+	}
+}
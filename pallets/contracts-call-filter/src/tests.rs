@@ -0,0 +1,60 @@
+use crate::{call_index, mock::*, AllowedCallFilter, Event};
+use frame_support::{assert_noop, assert_ok, traits::Contains};
+use sp_runtime::DispatchError;
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![1, 2, 3] })
+}
+
+#[test]
+fn call_index_is_the_pallet_and_call_index() {
+	assert_eq!(call_index(&remark()), Some(REMARK));
+	let call = RuntimeCall::ContractsCallFilter(crate::Call::disallow_call {
+		pallet_index: 0,
+		call_index: 0,
+	});
+	assert_eq!(call_index(&call), Some((1, 1)));
+	assert_eq!(call_index(&[0u8; 0]), None);
+}
+
+#[test]
+fn genesis_seeds_the_allowlist() {
+	new_test_ext(vec![REMARK]).execute_with(|| {
+		assert!(ContractsCallFilter::is_allowed(0, 0));
+		assert!(AllowedCallFilter::<Test>::contains(&remark()));
+	});
+}
+
+#[test]
+fn calls_are_disallowed_by_default() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert!(!AllowedCallFilter::<Test>::contains(&remark()));
+	});
+}
+
+#[test]
+fn allow_and_disallow_call_work() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert_ok!(ContractsCallFilter::allow_call(RuntimeOrigin::root(), 0, 0));
+		System::assert_last_event(Event::CallAllowed { pallet_index: 0, call_index: 0 }.into());
+		assert!(AllowedCallFilter::<Test>::contains(&remark()));
+
+		assert_ok!(ContractsCallFilter::disallow_call(RuntimeOrigin::root(), 0, 0));
+		System::assert_last_event(Event::CallDisallowed { pallet_index: 0, call_index: 0 }.into());
+		assert!(!AllowedCallFilter::<Test>::contains(&remark()));
+	});
+}
+
+#[test]
+fn editing_the_allowlist_requires_root() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert_noop!(
+			ContractsCallFilter::allow_call(RuntimeOrigin::signed(1), 0, 0),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ContractsCallFilter::disallow_call(RuntimeOrigin::signed(1), 0, 0),
+			DispatchError::BadOrigin
+		);
+	});
+}
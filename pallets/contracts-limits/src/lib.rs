@@ -0,0 +1,282 @@
+//! # Contracts Limits Pallet
+//!
+//! Keeps the limits which `pallet-contracts` enforces in runtime storage instead of compiling
+//! them into the runtime. This allows a single node binary to emulate the limits of different
+//! production chains. The limits are seeded from the chain spec and can be changed by root via
+//! [`Pallet::set_limits`].
+//!
+//! The runtime wires the limits into `pallet_contracts::Config` via [`MaxCodeLen`],
+//! [`MaxDebugBufferLen`] and [`DefaultDepositLimit`], and into its `Schedule` via
+//! [`Pallet::limits`].
+//!
+//! Limits are rejected if `pallet-contracts` would reject them in its integrity test, i.e. if the
+//! runtime memory can't accommodate code of the maximum length at the full depth of the call
+//! stack, or if the debug buffer is too small. Neither can the maximum code length be lowered
+//! below the length of already stored code.
+//!
+//! The depth of the call stack can not be configured here: `pallet_contracts::Config::CallStack`
+//! is an array type whose length is fixed at compile time.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{dispatch::DispatchClass, traits::Get, weights::Weight, RuntimeDebug};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::marker::PhantomData;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The minimum length of the debug buffer `pallet-contracts` accepts, in bytes.
+pub const MIN_DEBUG_BUFFER_LEN: u32 = 256;
+
+/// The maximum size of the stack of a contract `pallet-contracts` assumes, in bytes.
+const MAX_STACK_SIZE: u32 = 1024 * 1024;
+
+/// The limits applied to contracts.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ContractLimits<Balance> {
+	/// The total amount of memory available to the Wasm executor for running contracts, in
+	/// bytes. See `pallet_contracts::Limits::runtime_memory`.
+	pub runtime_memory: u32,
+	/// The maximum length of a contract's code, in bytes.
+	///
+	/// The limit is part of the type of the code stored by `pallet-contracts`, which is why it
+	/// can't be lowered below the length of already stored code: that code would no longer
+	/// decode.
+	pub max_code_len: u32,
+	/// The maximum length of the debug buffer, in bytes.
+	pub max_debug_buffer_len: u32,
+	/// The storage deposit limit applied to RPC calls which don't specify one.
+	pub default_deposit_limit: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The balance type of the deposit limit.
+		type Balance: Parameter
+			+ Member
+			+ MaxEncodedLen
+			+ Copy
+			+ Default
+			+ MaybeSerializeDeserialize;
+
+		/// The limits applied if none are set in storage.
+		#[pallet::constant]
+		type DefaultLimits: Get<ContractLimits<Self::Balance>>;
+
+		/// The number of frames of `pallet_contracts::Config::CallStack`.
+		#[pallet::constant]
+		type CallStackDepth: Get<u32>;
+
+		/// The maximum heap size of a contract, in bytes. See
+		/// `pallet_contracts::Limits::max_memory_size`.
+		#[pallet::constant]
+		type MaxHeapSize: Get<u32>;
+
+		/// The length of the longest code currently stored by `pallet-contracts`, in bytes.
+		type MaxStoredCodeLen: Get<u32>;
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultForLimits<T: Config>() -> ContractLimits<T::Balance> {
+		T::DefaultLimits::get()
+	}
+
+	/// The limits currently applied to contracts.
+	#[pallet::storage]
+	#[pallet::getter(fn limits)]
+	pub type Limits<T: Config> =
+		StorageValue<_, ContractLimits<T::Balance>, ValueQuery, DefaultForLimits<T>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub limits: ContractLimits<T::Balance>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { limits: T::DefaultLimits::get() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			if let Err(e) = Pallet::<T>::validate(&self.limits) {
+				panic!("Invalid `contractsLimits` in the genesis config: {e}");
+			}
+			Limits::<T>::put(self.limits);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The limits applied to contracts were changed.
+		LimitsSet { limits: ContractLimits<T::Balance> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The runtime memory can't accommodate code of the maximum length at the full depth of
+		/// the call stack.
+		CodeLenTooLarge,
+		/// The maximum length of the debug buffer doesn't exceed [`MIN_DEBUG_BUFFER_LEN`].
+		DebugBufferTooSmall,
+		/// The maximum code length is below the length of already stored code.
+		CodeLenBelowStoredCode,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Sets the limits applied to contracts.
+		///
+		/// The new limits apply to all calls dispatched after this one. Checking the length of
+		/// the stored code iterates over all of it, which is why only root may call this. As the
+		/// amount of stored code is unbounded, the call declares the largest weight an
+		/// operational extrinsic may have.
+		#[pallet::call_index(0)]
+		#[pallet::weight((Pallet::<T>::set_limits_weight(), DispatchClass::Operational))]
+		pub fn set_limits(
+			origin: OriginFor<T>,
+			limits: ContractLimits<T::Balance>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::validate(&limits).map_err(Error::<T>::from)?;
+			ensure!(
+				limits.max_code_len >= T::MaxStoredCodeLen::get(),
+				Error::<T>::CodeLenBelowStoredCode
+			);
+			Limits::<T>::put(limits);
+			Self::deposit_event(Event::LimitsSet { limits });
+			Ok(())
+		}
+	}
+}
+
+/// The reason [`ContractLimits`] are rejected by [`Pallet::validate`].
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum InvalidLimits {
+	/// The maximum code length isn't below `limit`, the largest length the runtime memory can
+	/// accommodate at the full depth of the call stack.
+	CodeLenTooLarge { max_code_len: u32, limit: u32 },
+	/// The maximum length of the debug buffer doesn't exceed [`MIN_DEBUG_BUFFER_LEN`].
+	DebugBufferTooSmall { max_debug_buffer_len: u32 },
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for InvalidLimits {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::CodeLenTooLarge { max_code_len, limit } => write!(
+				f,
+				"`maxCodeLen` of {max_code_len} bytes must be below {limit} bytes, the most the \
+				 `runtimeMemory` can accommodate at the full depth of the call stack"
+			),
+			Self::DebugBufferTooSmall { max_debug_buffer_len } => write!(
+				f,
+				"`maxDebugBufferLen` of {max_debug_buffer_len} bytes must exceed \
+				 {MIN_DEBUG_BUFFER_LEN} bytes"
+			),
+		}
+	}
+}
+
+impl<T> From<InvalidLimits> for Error<T> {
+	fn from(invalid: InvalidLimits) -> Self {
+		match invalid {
+			InvalidLimits::CodeLenTooLarge { .. } => Error::CodeLenTooLarge,
+			InvalidLimits::DebugBufferTooSmall { .. } => Error::DebugBufferTooSmall,
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The weight of [`Pallet::set_limits`].
+	fn set_limits_weight() -> Weight {
+		let block_weights = T::BlockWeights::get();
+		block_weights
+			.get(DispatchClass::Operational)
+			.max_extrinsic
+			.unwrap_or(block_weights.max_block)
+	}
+
+	/// Checks `limits` against the invariants `pallet-contracts` asserts in its integrity test.
+	pub fn validate(limits: &ContractLimits<T::Balance>) -> Result<(), InvalidLimits> {
+		let limit = Self::code_len_limit(limits.runtime_memory);
+		if limits.max_code_len >= limit {
+			return Err(InvalidLimits::CodeLenTooLarge { max_code_len: limits.max_code_len, limit })
+		}
+		if limits.max_debug_buffer_len <= MIN_DEBUG_BUFFER_LEN {
+			return Err(InvalidLimits::DebugBufferTooSmall {
+				max_debug_buffer_len: limits.max_debug_buffer_len,
+			})
+		}
+		Ok(())
+	}
+
+	/// Returns the exclusive upper bound of the maximum code length for `runtime_memory`.
+	///
+	/// Every frame of the call stack may hold the code, which takes up to 18 times its length
+	/// once compiled and 4 times that while being compiled, besides the heap and the stack.
+	/// Half of the runtime memory is reserved for the runtime itself.
+	pub fn code_len_limit(runtime_memory: u32) -> u32 {
+		runtime_memory
+			.saturating_div(2)
+			.saturating_div(T::CallStackDepth::get().saturating_add(1))
+			.saturating_sub(T::MaxHeapSize::get())
+			.saturating_sub(MAX_STACK_SIZE)
+			.saturating_div(18 * 4)
+	}
+}
+
+/// The maximum code length stored in [`Limits`], for `pallet_contracts::Config::MaxCodeLen`.
+pub struct MaxCodeLen<T>(PhantomData<T>);
+
+impl<T: Config> Get<u32> for MaxCodeLen<T> {
+	fn get() -> u32 {
+		Pallet::<T>::limits().max_code_len
+	}
+}
+
+/// The maximum debug buffer length stored in [`Limits`], for
+/// `pallet_contracts::Config::MaxDebugBufferLen`.
+pub struct MaxDebugBufferLen<T>(PhantomData<T>);
+
+impl<T: Config> Get<u32> for MaxDebugBufferLen<T> {
+	fn get() -> u32 {
+		Pallet::<T>::limits().max_debug_buffer_len
+	}
+}
+
+/// The default deposit limit stored in [`Limits`], for
+/// `pallet_contracts::Config::DefaultDepositLimit`.
+pub struct DefaultDepositLimit<T>(PhantomData<T>);
+
+impl<T: Config> Get<T::Balance> for DefaultDepositLimit<T> {
+	fn get() -> T::Balance {
+		Pallet::<T>::limits().default_deposit_limit
+	}
+}
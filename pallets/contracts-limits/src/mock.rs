@@ -0,0 +1,79 @@
+use crate::{self as pallet_contracts_limits, ContractLimits};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64},
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		ContractsLimits: pallet_contracts_limits,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const DefaultLimits: ContractLimits<u64> = ContractLimits {
+		runtime_memory: 128 * 1024 * 1024,
+		max_code_len: 123 * 1024,
+		max_debug_buffer_len: 2 * 1024 * 1024,
+		default_deposit_limit: 1_000,
+	};
+	pub static MaxStoredCodeLen: u32 = 0;
+}
+
+impl pallet_contracts_limits::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u64;
+	type DefaultLimits = DefaultLimits;
+	// The settings of Substrate's `kitchensink` runtime.
+	type CallStackDepth = ConstU32<5>;
+	type MaxHeapSize = ConstU32<{ 16 * 64 * 1024 }>;
+	type MaxStoredCodeLen = MaxStoredCodeLen;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		GenesisConfig::default().build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,111 @@
+use crate::{mock::*, Call, ContractLimits, Error, Event, InvalidLimits, Limits};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchClass, GetDispatchInfo},
+	traits::Get,
+};
+use sp_runtime::{BuildStorage, DispatchError};
+
+fn limits() -> ContractLimits<u64> {
+	DefaultLimits::get()
+}
+
+#[test]
+fn default_limits_are_valid() {
+	assert_ok!(ContractsLimits::validate(&limits()));
+}
+
+#[test]
+fn code_len_limit_matches_pallet_contracts() {
+	// (128 MiB / 2 / 6 - 1 MiB heap - 1 MiB stack) / (18 * 4)
+	assert_eq!(ContractsLimits::code_len_limit(128 * 1024 * 1024), 126_217);
+	assert_eq!(ContractsLimits::code_len_limit(0), 0);
+}
+
+#[test]
+fn set_limits_works() {
+	new_test_ext().execute_with(|| {
+		let new = ContractLimits { max_code_len: 64 * 1024, ..limits() };
+		assert_ok!(ContractsLimits::set_limits(RuntimeOrigin::root(), new));
+		assert_eq!(Limits::<Test>::get(), new);
+		System::assert_last_event(Event::LimitsSet { limits: new }.into());
+	});
+}
+
+#[test]
+fn set_limits_declares_the_largest_operational_weight() {
+	let info = Call::<Test>::set_limits { limits: limits() }.get_dispatch_info();
+	let max_extrinsic = <Test as frame_system::Config>::BlockWeights::get()
+		.get(DispatchClass::Operational)
+		.max_extrinsic;
+	assert_eq!(info.class, DispatchClass::Operational);
+	assert_eq!(Some(info.weight), max_extrinsic);
+}
+
+#[test]
+fn set_limits_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ContractsLimits::set_limits(RuntimeOrigin::signed(1), limits()),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn set_limits_rejects_code_exceeding_the_runtime_memory() {
+	new_test_ext().execute_with(|| {
+		let new = ContractLimits { max_code_len: 128 * 1024, ..limits() };
+		assert_eq!(
+			ContractsLimits::validate(&new),
+			Err(InvalidLimits::CodeLenTooLarge { max_code_len: 128 * 1024, limit: 126_217 })
+		);
+		assert_noop!(
+			ContractsLimits::set_limits(RuntimeOrigin::root(), new),
+			Error::<Test>::CodeLenTooLarge
+		);
+
+		// More runtime memory accommodates the longer code.
+		let new = ContractLimits { runtime_memory: 256 * 1024 * 1024, ..new };
+		assert_ok!(ContractsLimits::set_limits(RuntimeOrigin::root(), new));
+	});
+}
+
+#[test]
+fn set_limits_rejects_too_small_debug_buffer() {
+	new_test_ext().execute_with(|| {
+		let new = ContractLimits { max_debug_buffer_len: 256, ..limits() };
+		assert_noop!(
+			ContractsLimits::set_limits(RuntimeOrigin::root(), new),
+			Error::<Test>::DebugBufferTooSmall
+		);
+
+		let new = ContractLimits { max_debug_buffer_len: 257, ..limits() };
+		assert_ok!(ContractsLimits::set_limits(RuntimeOrigin::root(), new));
+	});
+}
+
+#[test]
+fn set_limits_rejects_code_len_below_stored_code() {
+	new_test_ext().execute_with(|| {
+		MaxStoredCodeLen::set(100 * 1024);
+
+		let new = ContractLimits { max_code_len: 100 * 1024 - 1, ..limits() };
+		assert_noop!(
+			ContractsLimits::set_limits(RuntimeOrigin::root(), new),
+			Error::<Test>::CodeLenBelowStoredCode
+		);
+
+		let new = ContractLimits { max_code_len: 100 * 1024, ..limits() };
+		assert_ok!(ContractsLimits::set_limits(RuntimeOrigin::root(), new));
+	});
+}
+
+#[test]
+#[should_panic(expected = "Invalid `contractsLimits` in the genesis config")]
+fn genesis_rejects_invalid_limits() {
+	let limits = ContractLimits { max_debug_buffer_len: 0, ..limits() };
+	let _ =
+		GenesisConfig { contracts_limits: crate::GenesisConfig { limits }, ..Default::default() }
+			.build_storage();
+}
@@ -0,0 +1,332 @@
+//! # Contracts Sponsorship Pallet
+//!
+//! Lets contracts pay the transaction fees of their callers, to prototype dApps with a gasless
+//! UX. A contract opts in by getting a fee budget via [`Pallet::set_budget`], which its owner,
+//! i.e. the account which uploaded its code, dispatches. A contract may also set its budget
+//! itself via `call_runtime`, and root can set the budget of any contract.
+//!
+//! The [`Sponsored`] signed extension wraps the runtime's fee payment extension. For a
+//! `Contracts::call` extrinsic to a contract whose budget covers the fee, it charges the fee to
+//! the contract account instead of the signer and deducts it from the budget. Otherwise the
+//! signer pays as usual, as they do if the contract account can't pay the fee. Only the fee in
+//! the native currency is sponsored: the value transferred and the storage deposit of the call
+//! are still paid by the signer, and transactions with a tip or which pay the fee in another
+//! asset aren't sponsored at all. Neither are calls to contracts nested in other calls, e.g. in
+//! a batch, nor calls while fees aren't charged at all, see [`Config::Feeless`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode, HasCompact};
+use frame_support::{
+	dispatch::{DispatchInfo, PostDispatchInfo},
+	storage::{storage_prefix, unhashed},
+	traits::{Get, IsSubType, PalletInfoAccess},
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, Dispatchable, PostDispatchInfoOf, Saturating, SignedExtension,
+		StaticLookup, Zero,
+	},
+	transaction_validity::{TransactionValidity, TransactionValidityError},
+	DispatchResult,
+};
+use sp_std::marker::PhantomData;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The balance type of transaction fees.
+pub type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as
+	pallet_transaction_payment::OnChargeTransaction<T>>::Balance;
+
+/// The type of the asset IDs `ChargeAssetTxPayment` charges fees in.
+pub type ChargeAssetIdOf<T> =
+	<<T as pallet_asset_tx_payment::Config>::OnChargeAssetTransaction as
+		pallet_asset_tx_payment::OnChargeAssetTransaction<T>>::AssetId;
+
+/// The runtime call type.
+pub type CallOf<T> = <T as frame_system::Config>::RuntimeCall;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_transaction_payment::Config + pallet_contracts::Config
+	{
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Whether fees are currently not charged, in which case nothing is sponsored and the
+		/// budgets are left untouched.
+		type Feeless: Get<bool>;
+	}
+
+	/// The remaining fee budgets of the contracts which sponsor their callers.
+	#[pallet::storage]
+	#[pallet::getter(fn budget)]
+	pub type Budgets<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The budget of a contract was set, or removed if `budget` is `None`.
+		BudgetSet { contract: T::AccountId, budget: Option<BalanceOf<T>> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Only the owner of the contract, the contract itself or root may set its budget.
+		NotOwner,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Sets the fee budget of `contract`, or opts it out of sponsoring if `budget` is `None`.
+		///
+		/// Must be dispatched by the owner of `contract`, see [`Pallet::owner`], the contract
+		/// itself, via `call_runtime`, or by root.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+		pub fn set_budget(
+			origin: OriginFor<T>,
+			contract: T::AccountId,
+			budget: Option<BalanceOf<T>>,
+		) -> DispatchResult {
+			if let Some(who) = frame_system::ensure_signed_or_root(origin)? {
+				ensure!(
+					who == contract || Some(who) == Self::owner(&contract),
+					Error::<T>::NotOwner
+				);
+			}
+			Budgets::<T>::set(&contract, budget);
+			Self::deposit_event(Event::BudgetSet { contract, budget });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the owner of `contract`, i.e. the account which uploaded its code.
+	///
+	/// `pallet_contracts` doesn't expose the owners of code, so it is read from the raw
+	/// `OwnerInfoOf` storage, in which the owner is the first field.
+	pub fn owner(contract: &T::AccountId) -> Option<T::AccountId> {
+		let code_hash = pallet_contracts::Pallet::<T>::code_hash(contract)?;
+		let prefix = storage_prefix(
+			<pallet_contracts::Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+			b"OwnerInfoOf",
+		);
+		// `OwnerInfoOf` uses the `Identity` hasher.
+		let raw = unhashed::get_raw(&[&prefix[..], &code_hash.encode()].concat())?;
+		T::AccountId::decode(&mut &raw[..]).ok()
+	}
+}
+
+/// A fee payment extension which [`Sponsored`] can wrap.
+pub trait FeePayment<T: Config> {
+	/// The tip the signer offers to the block author.
+	fn tip(&self) -> BalanceOf<T>;
+
+	/// Whether the signer chose to pay the fee in another asset than the native currency.
+	fn pays_in_asset(&self) -> bool;
+}
+
+// Neither extension exposes its fields, but both encode the tip first, as a compact integer.
+// `ChargeAssetTxPayment` encodes the asset ID after it.
+fn decode_fields<T: Config, Rest: Decode>(extension: &impl Encode) -> Option<(BalanceOf<T>, Rest)>
+where
+	BalanceOf<T>: HasCompact,
+{
+	extension.using_encoded(|mut encoded| {
+		let tip = <BalanceOf<T> as HasCompact>::Type::decode(&mut encoded).ok()?;
+		Some((tip.into(), Rest::decode(&mut encoded).ok()?))
+	})
+}
+
+impl<T: Config> FeePayment<T> for pallet_transaction_payment::ChargeTransactionPayment<T>
+where
+	BalanceOf<T>: HasCompact,
+{
+	fn tip(&self) -> BalanceOf<T> {
+		decode_fields::<T, ()>(self).map_or_else(Zero::zero, |(tip, ())| tip)
+	}
+
+	fn pays_in_asset(&self) -> bool {
+		false
+	}
+}
+
+impl<T: Config + pallet_asset_tx_payment::Config> FeePayment<T>
+	for pallet_asset_tx_payment::ChargeAssetTxPayment<T>
+where
+	BalanceOf<T>: HasCompact,
+{
+	fn tip(&self) -> BalanceOf<T> {
+		decode_fields::<T, ()>(self).map_or_else(Zero::zero, |(tip, ())| tip)
+	}
+
+	fn pays_in_asset(&self) -> bool {
+		decode_fields::<T, Option<ChargeAssetIdOf<T>>>(self)
+			.map_or(true, |(_, asset_id)| asset_id.is_some())
+	}
+}
+
+/// Wraps the fee payment extension `S` to charge the fees of sponsored contract calls to the
+/// called contract, see the [crate docs](crate).
+///
+/// The encoding and identifier are the ones of `S`, so clients sign transactions as if `S` was
+/// used directly.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct Sponsored<T, S>(pub S, PhantomData<T>);
+
+impl<T, S> From<S> for Sponsored<T, S> {
+	fn from(inner: S) -> Self {
+		Self(inner, PhantomData)
+	}
+}
+
+impl<T, S: sp_std::fmt::Debug> sp_std::fmt::Debug for Sponsored<T, S> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "Sponsored({:?})", self.0)
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T, S> Sponsored<T, S>
+where
+	T: Config,
+	CallOf<T>: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>
+		+ IsSubType<pallet_contracts::Call<T>>,
+	S: FeePayment<T>,
+{
+	/// Returns the contract sponsoring `call` and the fee it is charged, if any.
+	fn sponsor(
+		&self,
+		call: &CallOf<T>,
+		info: &DispatchInfo,
+		len: usize,
+	) -> Option<(T::AccountId, BalanceOf<T>)> {
+		if T::Feeless::get() {
+			return None
+		}
+		let dest = match call.is_sub_type()? {
+			pallet_contracts::Call::call { dest, .. } => dest,
+			_ => return None,
+		};
+		// The tip and the asset the fee is paid in are up to the signer, so neither tipped
+		// transactions nor ones paying in another asset than the budget's are sponsored.
+		if !self.0.tip().is_zero() || self.0.pays_in_asset() {
+			return None
+		}
+		let contract = T::Lookup::lookup(dest.clone()).ok()?;
+		let budget = Budgets::<T>::get(&contract)?;
+		let fee =
+			pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, Zero::zero());
+		(fee <= budget).then_some((contract, fee))
+	}
+}
+
+impl<T, S> SignedExtension for Sponsored<T, S>
+where
+	T: Config + Send + Sync,
+	CallOf<T>: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>
+		+ IsSubType<pallet_contracts::Call<T>>,
+	S: SignedExtension<AccountId = T::AccountId, Call = CallOf<T>> + FeePayment<T>,
+	BalanceOf<T>: Send + Sync,
+{
+	const IDENTIFIER: &'static str = S::IDENTIFIER;
+	type AccountId = S::AccountId;
+	type Call = S::Call;
+	type AdditionalSigned = S::AdditionalSigned;
+	/// The pre-dispatch data of `S` and the sponsor and the fee it was charged.
+	type Pre = (S::Pre, Option<(T::AccountId, BalanceOf<T>)>);
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		self.0.additional_signed()
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		// The signer pays if the contract can't, e.g. because it isn't endowed.
+		if let Some((contract, _)) = self.sponsor(call, info, len) {
+			if let Ok(validity) = self.0.validate(&contract, call, info, len) {
+				return Ok(validity)
+			}
+		}
+		self.0.validate(who, call, info, len)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if let Some((contract, fee)) = self.sponsor(call, info, len) {
+			if let Ok(pre) = self.0.clone().pre_dispatch(&contract, call, info, len) {
+				Budgets::<T>::mutate_exists(&contract, |budget| {
+					if let Some(budget) = budget {
+						*budget = budget.saturating_sub(fee);
+					}
+				});
+				return Ok((pre, Some((contract, fee))))
+			}
+		}
+		Ok((self.0.pre_dispatch(who, call, info, len)?, None))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (pre, sponsor) = match pre {
+			Some((pre, sponsor)) => (Some(pre), sponsor),
+			None => (None, None),
+		};
+		S::post_dispatch(pre, info, post_info, len, result)?;
+
+		// Return the part of the fee which was refunded to the contract to its budget.
+		if let Some((contract, fee)) = sponsor {
+			let actual_fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+				len as u32,
+				info,
+				post_info,
+				Zero::zero(),
+			);
+			Budgets::<T>::mutate_exists(&contract, |budget| {
+				if let Some(budget) = budget {
+					*budget = budget.saturating_add(fee.saturating_sub(actual_fee));
+				}
+			});
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,235 @@
+use crate::{mock::*, Budgets, Error, Event, FeePayment, Sponsored};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, PostDispatchInfo},
+	traits::Currency,
+	weights::Weight,
+};
+use pallet_asset_tx_payment::ChargeAssetTxPayment;
+use pallet_contracts::{CollectEvents, DebugInfo, Determinism};
+use pallet_contracts_primitives::Code;
+use sp_runtime::{traits::SignedExtension, AccountId32};
+
+const LEN: usize = 10;
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: Weight::from_parts(100, 0), ..Default::default() }
+}
+
+/// The fee of a call without a tip, before and after refunding the unused weight.
+const FEE: u64 = BASE_FEE + LEN as u64 + 100;
+const ACTUAL_FEE: u64 = BASE_FEE + LEN as u64 + 40;
+
+fn call_contract(dest: AccountId32) -> RuntimeCall {
+	RuntimeCall::Contracts(pallet_contracts::Call::call {
+		dest,
+		value: 0,
+		gas_limit: Weight::from_parts(1_000, 0),
+		storage_deposit_limit: None,
+		data: vec![],
+	})
+}
+
+/// Charges the fee of `call` signed by Alice, refunding the unused weight.
+fn charge(call: RuntimeCall, tip: u64) {
+	let extension = Sponsored::<Test, _>::from(ChargeAssetTxPayment::<Test>::from(tip, None));
+	let post_info = PostDispatchInfo {
+		actual_weight: Some(Weight::from_parts(40, 0)),
+		pays_fee: Default::default(),
+	};
+	assert_ok!(extension.validate(&ALICE, &call, &info(), LEN));
+	let pre = extension.pre_dispatch(&ALICE, &call, &info(), LEN).unwrap();
+	assert_ok!(Sponsored::<Test, ChargeAssetTxPayment<Test>>::post_dispatch(
+		Some(pre),
+		&info(),
+		&post_info,
+		LEN,
+		&Ok(())
+	));
+}
+
+fn set_budget(contract: AccountId32, budget: u64) {
+	assert_ok!(ContractsSponsorship::set_budget(RuntimeOrigin::root(), contract, Some(budget)));
+}
+
+#[test]
+fn reads_the_tip_of_the_wrapped_extension() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ChargeAssetTxPayment::<Test>::from(5, Some(1)).tip(), 5);
+		assert_eq!(ChargeAssetTxPayment::<Test>::from(0, None).tip(), 0);
+		assert_eq!(pallet_transaction_payment::ChargeTransactionPayment::<Test>::from(7).tip(), 7);
+	});
+}
+
+#[test]
+fn reads_the_asset_of_the_wrapped_extension() {
+	new_test_ext().execute_with(|| {
+		assert!(ChargeAssetTxPayment::<Test>::from(0, Some(1)).pays_in_asset());
+		assert!(!ChargeAssetTxPayment::<Test>::from(5, None).pays_in_asset());
+		assert!(
+			!pallet_transaction_payment::ChargeTransactionPayment::<Test>::from(0).pays_in_asset()
+		);
+	});
+}
+
+#[test]
+fn contract_pays_the_fee_of_sponsored_calls() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, 1_000);
+
+		charge(call_contract(CONTRACT), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		assert_eq!(Balances::free_balance(CONTRACT), 1_000 - ACTUAL_FEE);
+		// The refund for the unused weight is returned to the budget.
+		assert_eq!(ContractsSponsorship::budget(CONTRACT), Some(1_000 - ACTUAL_FEE));
+	});
+}
+
+#[test]
+fn signer_pays_if_the_budget_is_exhausted() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, FEE - 1);
+
+		charge(call_contract(CONTRACT), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - ACTUAL_FEE);
+		assert_eq!(Balances::free_balance(CONTRACT), 1_000);
+		assert_eq!(ContractsSponsorship::budget(CONTRACT), Some(FEE - 1));
+	});
+}
+
+#[test]
+fn signer_pays_if_the_contract_cannot() {
+	new_test_ext().execute_with(|| {
+		set_budget(POOR, 1_000);
+
+		charge(call_contract(POOR), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - ACTUAL_FEE);
+		assert_eq!(ContractsSponsorship::budget(POOR), Some(1_000));
+	});
+}
+
+#[test]
+fn signer_pays_tipped_calls() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, 1_000);
+
+		charge(call_contract(CONTRACT), 5);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - ACTUAL_FEE - 5);
+		assert_eq!(Balances::free_balance(CONTRACT), 1_000);
+		assert_eq!(ContractsSponsorship::budget(CONTRACT), Some(1_000));
+	});
+}
+
+#[test]
+fn signer_pays_calls_paying_in_assets() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, 1_000);
+
+		let call = call_contract(CONTRACT);
+		let sponsor = |asset_id| {
+			Sponsored::<Test, _>::from(ChargeAssetTxPayment::<Test>::from(0, asset_id)).sponsor(
+				&call,
+				&info(),
+				LEN,
+			)
+		};
+		assert_eq!(sponsor(None), Some((CONTRACT, FEE)));
+		assert_eq!(sponsor(Some(1)), None);
+	});
+}
+
+#[test]
+fn signer_pays_other_calls() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, 1_000);
+
+		charge(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - ACTUAL_FEE);
+		assert_eq!(ContractsSponsorship::budget(CONTRACT), Some(1_000));
+	});
+}
+
+#[test]
+fn nothing_is_sponsored_in_feeless_mode() {
+	new_test_ext().execute_with(|| {
+		set_budget(CONTRACT, 1_000);
+		Feeless::set(true);
+
+		charge(call_contract(CONTRACT), 0);
+		assert_eq!(Balances::free_balance(CONTRACT), 1_000);
+		assert_eq!(ContractsSponsorship::budget(CONTRACT), Some(1_000));
+	});
+}
+
+#[test]
+fn set_budget_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ContractsSponsorship::set_budget(RuntimeOrigin::signed(ALICE), CONTRACT, Some(1)),
+			Error::<Test>::NotOwner
+		);
+
+		assert_ok!(ContractsSponsorship::set_budget(
+			RuntimeOrigin::signed(CONTRACT),
+			CONTRACT,
+			Some(1)
+		));
+		System::assert_last_event(Event::BudgetSet { contract: CONTRACT, budget: Some(1) }.into());
+
+		assert_ok!(ContractsSponsorship::set_budget(RuntimeOrigin::root(), CONTRACT, None));
+		assert!(!Budgets::<Test>::contains_key(CONTRACT));
+	});
+}
+
+/// `(module (import "env" "memory" (memory 1 1)) (func (export "deploy")) (func (export
+/// "call")))`, the smallest code `pallet_contracts` accepts.
+const CODE: [u8; 65] = [
+	0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+	0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: `fn()`
+	0x02, 0x10, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x01,
+	0x01, 0x01, // import section: `env.memory`
+	0x03, 0x03, 0x02, 0x00, 0x00, // function section
+	0x07, 0x11, 0x02, 0x06, 0x64, 0x65, 0x70, 0x6c, 0x6f, 0x79, 0x00, 0x00, 0x04, 0x63, 0x61, 0x6c,
+	0x6c, 0x00, 0x01, // export section: `deploy` and `call`
+	0x0a, 0x07, 0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b, // code section: empty bodies
+];
+
+#[test]
+fn owner_of_the_code_may_set_the_budget() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&OWNER, 1_000_000);
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let code_hash =
+			Contracts::bare_upload_code(OWNER, CODE.to_vec(), None, Determinism::Enforced)
+				.unwrap()
+				.code_hash;
+		// The owner is the uploader of the code, not the instantiator of the contract.
+		let contract = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			Weight::from_parts(100_000_000_000, 1024 * 1024),
+			Some(100_000),
+			Code::Existing(code_hash),
+			vec![],
+			vec![],
+			DebugInfo::Skip,
+			CollectEvents::Skip,
+		)
+		.result
+		.unwrap()
+		.account_id;
+		assert_eq!(ContractsSponsorship::owner(&contract), Some(OWNER));
+		assert_eq!(ContractsSponsorship::owner(&CONTRACT), None);
+
+		assert_noop!(
+			ContractsSponsorship::set_budget(RuntimeOrigin::signed(ALICE), contract.clone(), None),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(ContractsSponsorship::set_budget(
+			RuntimeOrigin::signed(OWNER),
+			contract.clone(),
+			Some(1)
+		));
+		assert_eq!(ContractsSponsorship::budget(contract), Some(1));
+	});
+}
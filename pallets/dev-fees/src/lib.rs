@@ -0,0 +1,233 @@
+//! # Dev Fees Pallet
+//!
+//! A fee-less mode for UI development, in which transactions cost nothing so test wallets never
+//! run dry. The mode is seeded from the chain spec and can be switched by root via
+//! [`Pallet::set_feeless`].
+//!
+//! Fees are still computed as usual, so `TransactionPaymentApi::query_info` and the
+//! `TransactionFeePaid` events keep reporting what a transaction would have cost. Only charging
+//! them is skipped, by wrapping the fee handlers of the runtime:
+//!
+//! - [`FeelessAdapter`] wraps a `pallet_transaction_payment::OnChargeTransaction` and doesn't
+//!   withdraw any fee in fee-less mode.
+//! - [`FeelessAssetAdapter`] wraps a `pallet_asset_tx_payment::OnChargeAssetTransaction` and
+//!   refunds the whole fee in fee-less mode. The payer still needs to hold enough of the asset to
+//!   cover the fee when the transaction is validated.
+//!
+//! Other fee handlers, e.g. of sponsored transactions, can check the mode via [`IsFeeless`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::{
+	tokens::{fungibles, BalanceConversion},
+	Get,
+};
+use pallet_asset_tx_payment::OnChargeAssetTransaction;
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, Zero},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+use sp_std::marker::PhantomData;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The balance type of the assets fees can be paid in.
+pub type AssetBalanceOf<T> =
+	<<T as pallet_asset_tx_payment::Config>::Fungibles as fungibles::Inspect<
+		<T as frame_system::Config>::AccountId,
+	>>::Balance;
+
+/// The ID type of the assets fees can be paid in.
+pub type AssetIdOf<T> = <<T as pallet_asset_tx_payment::Config>::Fungibles as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::AssetId;
+
+/// A fee withdrawn in an asset.
+pub type CreditOf<T> = fungibles::Credit<
+	<T as frame_system::Config>::AccountId,
+	<T as pallet_asset_tx_payment::Config>::Fungibles,
+>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	/// Whether transactions are fee-less.
+	#[pallet::storage]
+	#[pallet::getter(fn feeless)]
+	pub type Feeless<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {
+		pub feeless: bool,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Feeless::<T>::put(self.feeless);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Fee-less mode was switched on or off.
+		FeelessSet { feeless: bool },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Switches fee-less mode on or off.
+		///
+		/// The mode applies from the next transaction on.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_feeless(origin: OriginFor<T>, feeless: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			Feeless::<T>::put(feeless);
+			Self::deposit_event(Event::FeelessSet { feeless });
+			Ok(())
+		}
+	}
+}
+
+/// Whether fee-less mode is on.
+pub struct IsFeeless<T>(PhantomData<T>);
+
+impl<T: Config> Get<bool> for IsFeeless<T> {
+	fn get() -> bool {
+		Pallet::<T>::feeless()
+	}
+}
+
+/// Charges fees via `Inner`, unless fee-less mode is on.
+///
+/// Whether a fee was withdrawn is tracked per transaction, so switching the mode never leaves a
+/// withdrawn fee uncorrected.
+pub struct FeelessAdapter<T, Inner>(PhantomData<(T, Inner)>);
+
+impl<T, Inner> OnChargeTransaction<T> for FeelessAdapter<T, Inner>
+where
+	T: Config + pallet_transaction_payment::Config,
+	Inner: OnChargeTransaction<T>,
+{
+	type Balance = Inner::Balance;
+	type LiquidityInfo = Option<Inner::LiquidityInfo>;
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::RuntimeCall,
+		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		if Pallet::<T>::feeless() {
+			return Ok(None)
+		}
+		Inner::withdraw_fee(who, call, dispatch_info, fee, tip).map(Some)
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		match already_withdrawn {
+			Some(already_withdrawn) => Inner::correct_and_deposit_fee(
+				who,
+				dispatch_info,
+				post_info,
+				corrected_fee,
+				tip,
+				already_withdrawn,
+			),
+			None => Ok(()),
+		}
+	}
+}
+
+/// Charges fees in assets via `Inner`, but refunds them in fee-less mode.
+///
+/// `pallet_asset_tx_payment` requires the fee withdrawn in an asset to be an actual credit of
+/// that asset, so unlike [`FeelessAdapter`] the fee can't be skipped altogether. The refunded fee
+/// is converted via `Converter`, which should be the conversion `Inner` uses, so the
+/// `AssetTxFeePaid` events report what the transaction would have cost.
+pub struct FeelessAssetAdapter<T, Inner, Converter>(PhantomData<(T, Inner, Converter)>);
+
+impl<T, Inner, Converter> OnChargeAssetTransaction<T> for FeelessAssetAdapter<T, Inner, Converter>
+where
+	T: Config + pallet_asset_tx_payment::Config,
+	Inner: OnChargeAssetTransaction<T, LiquidityInfo = CreditOf<T>>,
+	Converter: BalanceConversion<Inner::Balance, AssetIdOf<T>, AssetBalanceOf<T>>,
+{
+	type Balance = Inner::Balance;
+	type AssetId = Inner::AssetId;
+	type LiquidityInfo = Inner::LiquidityInfo;
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::RuntimeCall,
+		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
+		asset_id: Self::AssetId,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		Inner::withdraw_fee(who, call, dispatch_info, asset_id, fee, tip)
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
+		post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		paid: Self::LiquidityInfo,
+	) -> Result<(AssetBalanceOf<T>, AssetBalanceOf<T>), TransactionValidityError> {
+		if !Pallet::<T>::feeless() {
+			return Inner::correct_and_deposit_fee(
+				who,
+				dispatch_info,
+				post_info,
+				corrected_fee,
+				tip,
+				paid,
+			)
+		}
+
+		let asset_id = paid.asset();
+		Inner::correct_and_deposit_fee(
+			who,
+			dispatch_info,
+			post_info,
+			Zero::zero(),
+			Zero::zero(),
+			paid,
+		)?;
+		let convert = |balance| {
+			Converter::to_asset_balance(balance, asset_id)
+				.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))
+		};
+		Ok((convert(corrected_fee)?, convert(tip)?))
+	}
+}
@@ -0,0 +1,114 @@
+use crate::{mock::*, Event};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, PostDispatchInfo},
+	weights::Weight,
+};
+use pallet_asset_tx_payment::ChargeAssetTxPayment;
+use sp_runtime::{traits::SignedExtension, DispatchError};
+
+const LEN: usize = 10;
+const TIP: u64 = 5;
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: Weight::from_parts(100, 0), ..Default::default() }
+}
+
+/// The fee of a remark without the tip, given the post-dispatch weight of 40.
+const ACTUAL_FEE: u64 = BASE_FEE + LEN as u64 + 40;
+
+/// Charges the fee of a remark to Alice, optionally in [`ASSET`], and refunds the unused weight.
+fn charge(asset_id: Option<u32>) {
+	let post_info = PostDispatchInfo {
+		actual_weight: Some(Weight::from_parts(40, 0)),
+		pays_fee: Default::default(),
+	};
+	let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+	let pre = ChargeAssetTxPayment::<Test>::from(TIP, asset_id)
+		.pre_dispatch(&ALICE, &call, &info(), LEN)
+		.unwrap();
+	assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+		Some(pre),
+		&info(),
+		&post_info,
+		LEN,
+		&Ok(())
+	));
+}
+
+#[test]
+fn charges_fees_unless_feeless() {
+	new_test_ext(false).execute_with(|| {
+		charge(None);
+		assert_eq!(Balances::free_balance(ALICE), 1_000 - ACTUAL_FEE - TIP);
+	});
+}
+
+#[test]
+fn feeless_mode_reports_native_fees() {
+	new_test_ext(true).execute_with(|| {
+		charge(None);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+		System::assert_last_event(
+			pallet_transaction_payment::Event::TransactionFeePaid {
+				who: ALICE,
+				actual_fee: ACTUAL_FEE + TIP,
+				tip: TIP,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn charges_asset_fees_unless_feeless() {
+	new_test_ext(false).execute_with(|| {
+		charge(Some(ASSET));
+		let fee = (ACTUAL_FEE + TIP) * ASSET_MIN_BALANCE;
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000 - fee);
+		System::assert_last_event(
+			pallet_asset_tx_payment::Event::AssetTxFeePaid {
+				who: ALICE,
+				actual_fee: fee,
+				tip: TIP * ASSET_MIN_BALANCE,
+				asset_id: Some(ASSET),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn feeless_mode_refunds_and_reports_asset_fees() {
+	new_test_ext(true).execute_with(|| {
+		charge(Some(ASSET));
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000);
+		// The event reports the converted fee which would have been charged.
+		System::assert_last_event(
+			pallet_asset_tx_payment::Event::AssetTxFeePaid {
+				who: ALICE,
+				actual_fee: (ACTUAL_FEE + TIP) * ASSET_MIN_BALANCE,
+				tip: TIP * ASSET_MIN_BALANCE,
+				asset_id: Some(ASSET),
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn set_feeless_works() {
+	new_test_ext(false).execute_with(|| {
+		assert_noop!(
+			DevFees::set_feeless(RuntimeOrigin::signed(ALICE), true),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(DevFees::set_feeless(RuntimeOrigin::root(), true));
+		assert!(DevFees::feeless());
+		System::assert_last_event(Event::FeelessSet { feeless: true }.into());
+
+		charge(None);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+	});
+}
@@ -0,0 +1,120 @@
+//! # Dev Randomness Pallet
+//!
+//! A source of randomness which can be made deterministic, so contracts relying on randomness
+//! (e.g. lotteries and games) can be tested reproducibly and edge cases can be forced.
+//!
+//! If a seed is set, either in the chain spec or by root via [`Pallet::set_seed`], the random
+//! value for a subject is the hash of the seed, the current block number and the subject.
+//! Otherwise the randomness of [`Config::Fallback`] is returned.
+//!
+//! To force a specific outcome, e.g. a winning or losing draw, root can set the random value
+//! itself via [`Pallet::set_randomness`]. While it is set, it is returned verbatim for every
+//! subject, regardless of the seed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::Randomness;
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_runtime::traits::Hash;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, traits::Randomness};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The randomness used if no seed is set.
+		type Fallback: Randomness<Self::Hash, BlockNumberFor<Self>>;
+	}
+
+	/// The seed of the randomness, if it is deterministic.
+	#[pallet::storage]
+	#[pallet::getter(fn seed)]
+	pub type Seed<T: Config> = StorageValue<_, T::Hash, OptionQuery>;
+
+	/// The random value returned for every subject, if it is fixed.
+	#[pallet::storage]
+	#[pallet::getter(fn fixed_randomness)]
+	pub type FixedRandomness<T: Config> = StorageValue<_, T::Hash, OptionQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub seed: Option<T::Hash>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { seed: None }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			Seed::<T>::set(self.seed);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The seed was set, or removed if `None`.
+		SeedSet { seed: Option<T::Hash> },
+		/// The random value was fixed, or unfixed if `None`.
+		RandomnessSet { randomness: Option<T::Hash> },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Sets the seed of the randomness. Removing it restores the fallback randomness.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_seed(origin: OriginFor<T>, seed: Option<T::Hash>) -> DispatchResult {
+			ensure_root(origin)?;
+			Seed::<T>::set(seed);
+			Self::deposit_event(Event::SeedSet { seed });
+			Ok(())
+		}
+
+		/// Fixes the random value returned for every subject. Removing it restores the seeded
+		/// or fallback randomness.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_randomness(origin: OriginFor<T>, randomness: Option<T::Hash>) -> DispatchResult {
+			ensure_root(origin)?;
+			FixedRandomness::<T>::set(randomness);
+			Self::deposit_event(Event::RandomnessSet { randomness });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Randomness<T::Hash, BlockNumberFor<T>> for Pallet<T> {
+	fn random(subject: &[u8]) -> (T::Hash, BlockNumberFor<T>) {
+		if let Some(randomness) = FixedRandomness::<T>::get() {
+			return (randomness, frame_system::Pallet::<T>::block_number())
+		}
+		match Seed::<T>::get() {
+			Some(seed) => {
+				let block_number = frame_system::Pallet::<T>::block_number();
+				(T::Hashing::hash_of(&(seed, block_number, subject)), block_number)
+			},
+			None => T::Fallback::random(subject),
+		}
+	}
+}
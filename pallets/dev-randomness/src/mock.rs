@@ -0,0 +1,77 @@
+use crate as pallet_dev_randomness;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Randomness};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		DevRandomness: pallet_dev_randomness,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+/// The random value of the fallback, regardless of the subject.
+pub const FALLBACK: H256 = H256::repeat_byte(0xff);
+
+pub struct Fallback;
+
+impl Randomness<H256, u64> for Fallback {
+	fn random(_subject: &[u8]) -> (H256, u64) {
+		(FALLBACK, 0)
+	}
+}
+
+impl pallet_dev_randomness::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Fallback = Fallback;
+}
+
+pub fn new_test_ext(seed: Option<H256>) -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = GenesisConfig {
+		dev_randomness: pallet_dev_randomness::GenesisConfig { seed },
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,86 @@
+use crate::{mock::*, Event};
+use frame_support::{assert_noop, assert_ok, traits::Randomness};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash},
+	DispatchError,
+};
+
+const SEED: H256 = H256::repeat_byte(1);
+
+#[test]
+fn falls_back_without_a_seed() {
+	new_test_ext(None).execute_with(|| {
+		assert_eq!(DevRandomness::random(b"subject"), (FALLBACK, 0));
+	});
+}
+
+#[test]
+fn genesis_seed_makes_randomness_deterministic() {
+	new_test_ext(Some(SEED)).execute_with(|| {
+		let expected = BlakeTwo256::hash_of(&(SEED, 1u64, &b"subject"[..]));
+		assert_eq!(DevRandomness::random(b"subject"), (expected, 1));
+		assert_eq!(DevRandomness::random(b"subject"), (expected, 1));
+	});
+}
+
+#[test]
+fn randomness_depends_on_subject_and_block() {
+	new_test_ext(Some(SEED)).execute_with(|| {
+		let (first, _) = DevRandomness::random(b"first");
+		assert_ne!(first, DevRandomness::random(b"second").0);
+
+		System::set_block_number(2);
+		assert_ne!(first, DevRandomness::random(b"first").0);
+	});
+}
+
+#[test]
+fn set_seed_works() {
+	new_test_ext(None).execute_with(|| {
+		assert_ok!(DevRandomness::set_seed(RuntimeOrigin::root(), Some(SEED)));
+		assert_eq!(DevRandomness::seed(), Some(SEED));
+		System::assert_last_event(Event::SeedSet { seed: Some(SEED) }.into());
+		assert_ne!(DevRandomness::random(b"subject").0, FALLBACK);
+
+		assert_ok!(DevRandomness::set_seed(RuntimeOrigin::root(), None));
+		System::assert_last_event(Event::SeedSet { seed: None }.into());
+		assert_eq!(DevRandomness::random(b"subject"), (FALLBACK, 0));
+	});
+}
+
+#[test]
+fn set_seed_requires_root() {
+	new_test_ext(None).execute_with(|| {
+		assert_noop!(
+			DevRandomness::set_seed(RuntimeOrigin::signed(1), Some(SEED)),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn set_randomness_returns_the_value_verbatim() {
+	new_test_ext(Some(SEED)).execute_with(|| {
+		let fixed = H256::repeat_byte(2);
+		assert_ok!(DevRandomness::set_randomness(RuntimeOrigin::root(), Some(fixed)));
+		System::assert_last_event(Event::RandomnessSet { randomness: Some(fixed) }.into());
+		assert_eq!(DevRandomness::random(b"first"), (fixed, 1));
+		assert_eq!(DevRandomness::random(b"second"), (fixed, 1));
+
+		// Unfixing it restores the seeded randomness.
+		assert_ok!(DevRandomness::set_randomness(RuntimeOrigin::root(), None));
+		let expected = BlakeTwo256::hash_of(&(SEED, 1u64, &b"first"[..]));
+		assert_eq!(DevRandomness::random(b"first"), (expected, 1));
+	});
+}
+
+#[test]
+fn set_randomness_requires_root() {
+	new_test_ext(None).execute_with(|| {
+		assert_noop!(
+			DevRandomness::set_randomness(RuntimeOrigin::signed(1), Some(SEED)),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,273 @@
+//! # Fee Model Pallet
+//!
+//! Keeps the fee model of `pallet-transaction-payment` in runtime storage instead of compiling
+//! it into the runtime, so dApps can be tested against the fees of different chains, including
+//! fees which react to congestion. The model is seeded from the chain spec and can be changed by
+//! root via [`Pallet::set_fee_model`].
+//!
+//! The runtime wires the model into `pallet_transaction_payment::Config` via
+//! [`FeeMultiplierUpdate`] and [`WeightToFee`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::{ConstU32, Get},
+	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
+	BoundedVec, RuntimeDebug,
+};
+use pallet_transaction_payment::{Multiplier, MultiplierUpdate, TargetedFeeAdjustment};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+	traits::{Bounded, Convert, One, Zero},
+	Perbill, Perquintill,
+};
+use sp_std::marker::PhantomData;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// The maximum number of terms of the weight to fee polynomial.
+pub type MaxFeeCoefficients = ConstU32<4>;
+
+/// The fee model applied to transactions.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FeeModel<Balance> {
+	/// How the fee multiplier adapts to congestion, or `None` to keep it at one.
+	pub multiplier: Option<TargetedAdjustment>,
+	/// The polynomial which converts the weight of a transaction into its fee, in terms of its
+	/// `ref_time`. `[{ coeffInteger: 1, coeffFrac: 0, negative: false, degree: 1 }]` is
+	/// `IdentityFee`.
+	pub weight_to_fee: BoundedVec<FeeCoefficient<Balance>, MaxFeeCoefficients>,
+}
+
+/// The parameters of a `TargetedFeeAdjustment` of the fee multiplier.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TargetedAdjustment {
+	/// The block fullness the multiplier is adjusted towards.
+	pub target_block_fullness: Perquintill,
+	/// How fast the multiplier reacts to the block fullness.
+	pub adjustment_variable: Multiplier,
+	/// The lower bound of the multiplier.
+	pub minimum_multiplier: Multiplier,
+	/// The upper bound of the multiplier.
+	pub maximum_multiplier: Multiplier,
+}
+
+/// A term of the weight to fee polynomial, see `WeightToFeeCoefficient`.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FeeCoefficient<Balance> {
+	/// The integral part of the coefficient.
+	pub coeff_integer: Balance,
+	/// The fractional part of the coefficient.
+	pub coeff_frac: Perbill,
+	/// Whether the term is subtracted.
+	pub negative: bool,
+	/// The degree of the term.
+	pub degree: u8,
+}
+
+impl<Balance: Copy> From<&FeeCoefficient<Balance>> for WeightToFeeCoefficient<Balance> {
+	fn from(coefficient: &FeeCoefficient<Balance>) -> Self {
+		WeightToFeeCoefficient {
+			coeff_integer: coefficient.coeff_integer,
+			coeff_frac: coefficient.coeff_frac,
+			negative: coefficient.negative,
+			degree: coefficient.degree,
+		}
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::AtLeast32BitUnsigned;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The balance type of fees.
+		type Balance: Parameter
+			+ Member
+			+ MaxEncodedLen
+			+ Copy
+			+ Default
+			+ AtLeast32BitUnsigned
+			+ MaybeSerializeDeserialize;
+
+		/// The fee model applied if none is set in storage.
+		#[pallet::constant]
+		type DefaultFeeModel: Get<FeeModel<Self::Balance>>;
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultForModel<T: Config>() -> FeeModel<T::Balance> {
+		T::DefaultFeeModel::get()
+	}
+
+	/// The fee model currently applied to transactions.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_model)]
+	pub type Model<T: Config> =
+		StorageValue<_, FeeModel<T::Balance>, ValueQuery, DefaultForModel<T>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub fee_model: FeeModel<T::Balance>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { fee_model: T::DefaultFeeModel::get() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			Model::<T>::put(&self.fee_model);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The fee model applied to transactions was changed.
+		FeeModelSet { fee_model: FeeModel<T::Balance> },
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Sets the fee model applied to transactions.
+		///
+		/// The new weight to fee polynomial applies to all transactions after this one. The
+		/// fee multiplier is adjusted according to the new model from the end of this block on.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_fee_model(
+			origin: OriginFor<T>,
+			fee_model: FeeModel<T::Balance>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Model::<T>::put(&fee_model);
+			Self::deposit_event(Event::FeeModelSet { fee_model });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The targeted adjustment of the multiplier stored in [`Model`], if any.
+	fn targeted_adjustment() -> Option<TargetedAdjustment> {
+		Self::fee_model().multiplier
+	}
+}
+
+/// Returns the target block fullness of the [`TargetedAdjustment`] in storage.
+pub struct TargetBlockFullness<T>(PhantomData<T>);
+
+impl<T: Config> Get<Perquintill> for TargetBlockFullness<T> {
+	fn get() -> Perquintill {
+		Pallet::<T>::targeted_adjustment().map_or_else(Zero::zero, |a| a.target_block_fullness)
+	}
+}
+
+/// Returns the adjustment variable of the [`TargetedAdjustment`] in storage.
+pub struct AdjustmentVariable<T>(PhantomData<T>);
+
+impl<T: Config> Get<Multiplier> for AdjustmentVariable<T> {
+	fn get() -> Multiplier {
+		Pallet::<T>::targeted_adjustment().map_or_else(Zero::zero, |a| a.adjustment_variable)
+	}
+}
+
+/// Returns the minimum multiplier of the [`TargetedAdjustment`] in storage.
+pub struct MinimumMultiplier<T>(PhantomData<T>);
+
+impl<T: Config> Get<Multiplier> for MinimumMultiplier<T> {
+	fn get() -> Multiplier {
+		Pallet::<T>::targeted_adjustment().map_or_else(Zero::zero, |a| a.minimum_multiplier)
+	}
+}
+
+/// Returns the maximum multiplier of the [`TargetedAdjustment`] in storage.
+pub struct MaximumMultiplier<T>(PhantomData<T>);
+
+impl<T: Config> Get<Multiplier> for MaximumMultiplier<T> {
+	fn get() -> Multiplier {
+		Pallet::<T>::targeted_adjustment().map_or_else(Bounded::max_value, |a| a.maximum_multiplier)
+	}
+}
+
+type Targeted<T> = TargetedFeeAdjustment<
+	T,
+	TargetBlockFullness<T>,
+	AdjustmentVariable<T>,
+	MinimumMultiplier<T>,
+	MaximumMultiplier<T>,
+>;
+
+/// Updates the fee multiplier according to [`FeeModel::multiplier`], for
+/// `pallet_transaction_payment::Config::FeeMultiplierUpdate`.
+///
+/// Applies the stored [`TargetedAdjustment`], or keeps the multiplier at one if there is none.
+pub struct FeeMultiplierUpdate<T>(PhantomData<T>);
+
+impl<T: Config> Convert<Multiplier, Multiplier> for FeeMultiplierUpdate<T> {
+	fn convert(previous: Multiplier) -> Multiplier {
+		match Pallet::<T>::targeted_adjustment() {
+			Some(_) => Targeted::<T>::convert(previous),
+			None => Multiplier::one(),
+		}
+	}
+}
+
+impl<T: Config> MultiplierUpdate for FeeMultiplierUpdate<T> {
+	fn min() -> Multiplier {
+		MinimumMultiplier::<T>::get()
+	}
+
+	fn max() -> Multiplier {
+		MaximumMultiplier::<T>::get()
+	}
+
+	fn target() -> Perquintill {
+		TargetBlockFullness::<T>::get()
+	}
+
+	fn variability() -> Multiplier {
+		AdjustmentVariable::<T>::get()
+	}
+}
+
+/// Converts weight to fees according to [`FeeModel::weight_to_fee`], for
+/// `pallet_transaction_payment::Config::WeightToFee`.
+pub struct WeightToFee<T>(PhantomData<T>);
+
+impl<T: Config> WeightToFeePolynomial for WeightToFee<T> {
+	type Balance = T::Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		Pallet::<T>::fee_model().weight_to_fee.iter().map(Into::into).collect()
+	}
+}
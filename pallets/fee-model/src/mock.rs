@@ -0,0 +1,121 @@
+use crate as pallet_fee_model;
+use crate::{FeeCoefficient, FeeModel};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, ConstU8},
+	weights::IdentityFee,
+	BoundedVec,
+};
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage, Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		TransactionPayment: pallet_transaction_payment,
+		TransactionFees: pallet_fee_model,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type HoldIdentifier = ();
+	type MaxHolds = ();
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OperationalFeeMultiplier = ConstU8<5>;
+	type WeightToFee = pallet_fee_model::WeightToFee<Self>;
+	type LengthToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = pallet_fee_model::FeeMultiplierUpdate<Self>;
+}
+
+/// A fee model of `coeff_integer + coeff_frac` times the weight, without a multiplier.
+pub fn linear(coeff_integer: u64, coeff_frac: Perbill) -> FeeModel<u64> {
+	FeeModel {
+		multiplier: None,
+		weight_to_fee: BoundedVec::truncate_from(vec![FeeCoefficient {
+			coeff_integer,
+			coeff_frac,
+			negative: false,
+			degree: 1,
+		}]),
+	}
+}
+
+parameter_types! {
+	pub DefaultFeeModel: FeeModel<u64> = linear(1, Perbill::zero());
+}
+
+impl pallet_fee_model::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = u64;
+	type DefaultFeeModel = DefaultFeeModel;
+}
+
+pub const ALICE: u64 = 1;
+
+/// Builds the genesis state with `fee_model`.
+pub fn new_test_ext(fee_model: FeeModel<u64>) -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = GenesisConfig {
+		transaction_fees: pallet_fee_model::GenesisConfig { fee_model },
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,100 @@
+use crate::{
+	mock::*, AdjustmentVariable, Event, FeeMultiplierUpdate, MaximumMultiplier, MinimumMultiplier,
+	TargetBlockFullness, TargetedAdjustment, WeightToFee,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::Get,
+	weights::{Weight, WeightToFee as _},
+};
+use pallet_transaction_payment::Multiplier;
+use sp_runtime::{
+	traits::{Bounded, Convert, One, Zero},
+	DispatchError, FixedPointNumber, Perbill, Perquintill,
+};
+
+fn adjustment() -> TargetedAdjustment {
+	TargetedAdjustment {
+		target_block_fullness: Perquintill::from_percent(25),
+		adjustment_variable: Multiplier::saturating_from_rational(3, 100),
+		minimum_multiplier: Multiplier::saturating_from_rational(1, 10),
+		maximum_multiplier: Multiplier::saturating_from_integer(2),
+	}
+}
+
+fn weight_to_fee(ref_time: u64) -> u64 {
+	WeightToFee::<Test>::weight_to_fee(&Weight::from_parts(ref_time, 0))
+}
+
+#[test]
+fn genesis_seeds_the_fee_model() {
+	new_test_ext(linear(3, Perbill::zero())).execute_with(|| {
+		assert_eq!(TransactionFees::fee_model(), linear(3, Perbill::zero()));
+		assert_eq!(weight_to_fee(100), 300);
+	});
+}
+
+#[test]
+fn weight_to_fee_follows_the_polynomial() {
+	new_test_ext(linear(1, Perbill::zero())).execute_with(|| {
+		assert_eq!(weight_to_fee(100), 100);
+
+		assert_ok!(TransactionFees::set_fee_model(
+			RuntimeOrigin::root(),
+			linear(2, Perbill::from_percent(50))
+		));
+		assert_eq!(weight_to_fee(100), 250);
+	});
+}
+
+#[test]
+fn multiplier_stays_at_one_without_an_adjustment() {
+	new_test_ext(linear(1, Perbill::zero())).execute_with(|| {
+		assert_eq!(FeeMultiplierUpdate::<Test>::convert(Multiplier::zero()), Multiplier::one());
+		assert_eq!(
+			FeeMultiplierUpdate::<Test>::convert(Multiplier::saturating_from_integer(5)),
+			Multiplier::one()
+		);
+		assert_eq!(MaximumMultiplier::<Test>::get(), Multiplier::max_value());
+	});
+}
+
+#[test]
+fn multiplier_follows_the_targeted_adjustment() {
+	let fee_model =
+		crate::FeeModel { multiplier: Some(adjustment()), ..linear(1, Perbill::zero()) };
+	new_test_ext(fee_model).execute_with(|| {
+		let adjustment = adjustment();
+		assert_eq!(TargetBlockFullness::<Test>::get(), adjustment.target_block_fullness);
+		assert_eq!(AdjustmentVariable::<Test>::get(), adjustment.adjustment_variable);
+		assert_eq!(MinimumMultiplier::<Test>::get(), adjustment.minimum_multiplier);
+		assert_eq!(MaximumMultiplier::<Test>::get(), adjustment.maximum_multiplier);
+
+		// The block is empty, so the multiplier decreases, but within its bounds.
+		let next = FeeMultiplierUpdate::<Test>::convert(Multiplier::one());
+		assert!(next < Multiplier::one() && next > adjustment.minimum_multiplier, "{next:?}");
+		assert_eq!(
+			FeeMultiplierUpdate::<Test>::convert(Multiplier::zero()),
+			adjustment.minimum_multiplier
+		);
+		assert_eq!(
+			FeeMultiplierUpdate::<Test>::convert(Multiplier::saturating_from_integer(5)),
+			adjustment.maximum_multiplier
+		);
+	});
+}
+
+#[test]
+fn set_fee_model_works() {
+	new_test_ext(linear(1, Perbill::zero())).execute_with(|| {
+		let fee_model = linear(2, Perbill::zero());
+		assert_noop!(
+			TransactionFees::set_fee_model(RuntimeOrigin::signed(ALICE), fee_model.clone()),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(TransactionFees::set_fee_model(RuntimeOrigin::root(), fee_model.clone()));
+		assert_eq!(TransactionFees::fee_model(), fee_model);
+		System::assert_last_event(Event::FeeModelSet { fee_model }.into());
+	});
+}
@@ -0,0 +1,116 @@
+//! # Genesis NFTs Pallet
+//!
+//! `pallet-nfts` has no genesis config. This pallet creates NFT collections and mints items at
+//! genesis, so contracts interacting with native NFTs can be tested against a known state.
+//!
+//! Collections are created in the order of [`GenesisConfig::collections`], so they are assigned
+//! the IDs `0, 1, 2, ..`. Their owners pay the usual deposits and need to be endowed.
+//!
+//! Items can only be minted into collections created at genesis, and only once. The node checks
+//! this via [`GenesisConfig::validate`] when it loads a chain spec; the genesis build panics with
+//! a description of the offending entry if the config is invalid anyway or a deposit can't be
+//! paid.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		pallet_prelude::*,
+		traits::tokens::nonfungibles_v2::{Create, Mutate},
+	};
+	use pallet_nfts::{
+		CollectionConfig, CollectionSettings, ItemConfig, ItemSettings, MintSettings,
+	};
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_nfts::Config<CollectionId = u32, ItemId = u32>
+	{
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The owners of the collections to create, who are their admins as well.
+		pub collections: Vec<T::AccountId>,
+		/// The items to mint, as `(collection, item, owner)`.
+		pub items: Vec<(u32, u32, T::AccountId)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { collections: Vec::new(), items: Vec::new() }
+		}
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> GenesisConfig<T> {
+		/// Checks that every item is minted into a collection created at genesis, and only once.
+		pub fn validate(&self) -> Result<(), String> {
+			let mut minted = std::collections::BTreeSet::new();
+			for (collection, item, _) in &self.items {
+				if *collection as usize >= self.collections.len() {
+					return Err(format!(
+						"`genesisNfts` mints item {item} into collection {collection}, but only \
+						 {} collections are created at genesis",
+						self.collections.len(),
+					))
+				}
+				if !minted.insert((collection, item)) {
+					return Err(format!(
+						"`genesisNfts` mints item {item} of collection {collection} twice"
+					))
+				}
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			if let Err(e) = self.validate() {
+				panic!("Invalid genesis config: {e}");
+			}
+
+			let config = CollectionConfig {
+				settings: CollectionSettings::all_enabled(),
+				max_supply: None,
+				mint_settings: MintSettings::default(),
+			};
+			for (collection, owner) in self.collections.iter().enumerate() {
+				if let Err(e) = pallet_nfts::Pallet::<T>::create_collection(owner, owner, &config) {
+					panic!(
+						"Unable to create the genesis NFT collection {collection} of {owner:?}, \
+						 make sure the owner is endowed with the collection deposit: {e:?}"
+					);
+				}
+			}
+
+			let config = ItemConfig { settings: ItemSettings::all_enabled() };
+			for (collection, item, owner) in &self.items {
+				if let Err(e) =
+					pallet_nfts::Pallet::<T>::mint_into(collection, item, owner, &config, true)
+				{
+					panic!(
+						"Unable to mint the genesis NFT {item} of collection {collection} to \
+						 {owner:?}, make sure the collection owner is endowed with the item \
+						 deposit: {e:?}"
+					);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,49 @@
+use crate::{mock::*, GenesisConfig};
+use frame_support::traits::tokens::nonfungibles_v2::Inspect;
+use sp_runtime::AccountId32;
+
+fn config(
+	collections: Vec<AccountId32>,
+	items: Vec<(u32, u32, AccountId32)>,
+) -> GenesisConfig<Test> {
+	GenesisConfig { collections, items }
+}
+
+#[test]
+fn genesis_creates_collections_and_mints_items() {
+	let nfts = config(vec![ALICE, BOB], vec![(0, 0, BOB), (1, 7, ALICE)]);
+	new_test_ext(nfts).execute_with(|| {
+		assert_eq!(<Nfts as Inspect<_>>::collection_owner(&0), Some(ALICE));
+		assert_eq!(<Nfts as Inspect<_>>::collection_owner(&1), Some(BOB));
+		assert_eq!(<Nfts as Inspect<_>>::owner(&0, &0), Some(BOB));
+		assert_eq!(<Nfts as Inspect<_>>::owner(&1, &7), Some(ALICE));
+
+		// The collection owners pay the deposits.
+		assert_eq!(Balances::reserved_balance(ALICE), COLLECTION_DEPOSIT + ITEM_DEPOSIT);
+		assert_eq!(Balances::reserved_balance(BOB), COLLECTION_DEPOSIT + ITEM_DEPOSIT);
+	});
+}
+
+#[test]
+fn validate_rejects_items_of_unknown_collections() {
+	let error = config(vec![ALICE], vec![(1, 0, BOB)]).validate().unwrap_err();
+	assert!(error.contains("collection 1"), "{error}");
+}
+
+#[test]
+fn validate_rejects_duplicate_items() {
+	let error = config(vec![ALICE], vec![(0, 0, BOB), (0, 0, ALICE)]).validate().unwrap_err();
+	assert!(error.contains("twice"), "{error}");
+}
+
+#[test]
+#[should_panic(expected = "Invalid genesis config: `genesisNfts` mints item 0 into collection 1")]
+fn genesis_panics_on_invalid_config() {
+	new_test_ext(config(vec![ALICE], vec![(1, 0, BOB)]));
+}
+
+#[test]
+#[should_panic(expected = "Unable to create the genesis NFT collection 0")]
+fn genesis_panics_if_the_owner_cannot_pay_the_deposit() {
+	new_test_ext(config(vec![AccountId32::new([3; 32])], vec![]));
+}
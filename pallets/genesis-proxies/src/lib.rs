@@ -0,0 +1,104 @@
+//! # Genesis Proxies Pallet
+//!
+//! `pallet-proxy` has no genesis config. This pallet adds proxies at genesis, so flows in which
+//! contracts are operated via a proxy can be tested without setting them up first.
+//!
+//! The delegators reserve the usual proxy deposits and need to be endowed. The node checks the
+//! proxies via [`GenesisConfig::validate`] when it loads a chain spec; the genesis build panics
+//! with the same error otherwise.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use sp_runtime::traits::Zero;
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_proxy::Config {
+		/// The proxy type as it is specified in the chain spec.
+		///
+		/// `pallet_proxy::Config::ProxyType` isn't required to be serializable.
+		type GenesisProxyType: Member
+			+ MaybeSerializeDeserialize
+			+ Into<<Self as pallet_proxy::Config>::ProxyType>;
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The proxies to add, as `(delegator, delegate, proxy_type)`, without a delay.
+		pub proxies: Vec<(T::AccountId, T::AccountId, T::GenesisProxyType)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { proxies: Vec::new() }
+		}
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> GenesisConfig<T> {
+		/// Checks that `pallet-proxy` accepts every proxy, i.e. that no account is its own proxy,
+		/// no proxy is added twice and no delegator exceeds `MaxProxies`.
+		pub fn validate(&self) -> Result<(), String> {
+			let mut added = std::collections::BTreeSet::new();
+			let mut proxies = std::collections::BTreeMap::<_, u32>::new();
+			for (delegator, delegate, proxy_type) in &self.proxies {
+				if delegator == delegate {
+					return Err(format!("`genesisProxies` makes {delegator:?} its own proxy"))
+				}
+				let proxy_type: <T as pallet_proxy::Config>::ProxyType = proxy_type.clone().into();
+				if !added.insert((delegator, delegate, proxy_type.clone())) {
+					return Err(format!(
+						"`genesisProxies` adds {delegate:?} as a {proxy_type:?} proxy of \
+						 {delegator:?} twice"
+					))
+				}
+				let count = proxies.entry(delegator).or_default();
+				*count += 1;
+				let max = <T as pallet_proxy::Config>::MaxProxies::get();
+				if *count > max {
+					return Err(format!(
+						"`genesisProxies` adds more than {max} proxies to {delegator:?}"
+					))
+				}
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			if let Err(e) = self.validate() {
+				panic!("Invalid genesis config: {e}");
+			}
+
+			for (delegator, delegate, proxy_type) in &self.proxies {
+				if let Err(e) = pallet_proxy::Pallet::<T>::add_proxy_delegate(
+					delegator,
+					delegate.clone(),
+					proxy_type.clone().into(),
+					Zero::zero(),
+				) {
+					panic!(
+						"Unable to add the genesis proxy {delegate:?} of {delegator:?}, make sure \
+						 the delegator is endowed with the proxy deposit: {e:?}"
+					);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,66 @@
+use crate::{mock::*, GenesisConfig};
+
+fn config(proxies: Vec<(u64, u64, ProxyType)>) -> GenesisConfig<Test> {
+	GenesisConfig { proxies }
+}
+
+#[test]
+fn genesis_adds_proxies() {
+	let proxies = config(vec![
+		(ALICE, BOB, ProxyType::Any),
+		(ALICE, CHARLIE, ProxyType::JustRemarks),
+		(BOB, ALICE, ProxyType::JustRemarks),
+	]);
+	new_test_ext(proxies).execute_with(|| {
+		let (alice, deposit) = Proxy::proxies(ALICE);
+		let alice: Vec<_> = alice.iter().map(|p| (p.delegate, p.proxy_type, p.delay)).collect();
+		assert_eq!(alice, vec![(BOB, ProxyType::Any, 0), (CHARLIE, ProxyType::JustRemarks, 0)]);
+		// The delegators pay the deposits.
+		assert_eq!(deposit, PROXY_DEPOSIT_BASE + 2 * PROXY_DEPOSIT_FACTOR);
+		assert_eq!(Balances::reserved_balance(ALICE), deposit);
+		assert_eq!(Balances::reserved_balance(BOB), PROXY_DEPOSIT_BASE + PROXY_DEPOSIT_FACTOR);
+	});
+}
+
+#[test]
+fn validate_rejects_self_proxies() {
+	let error = config(vec![(ALICE, ALICE, ProxyType::Any)]).validate().unwrap_err();
+	assert!(error.contains("its own proxy"), "{error}");
+}
+
+#[test]
+fn validate_rejects_duplicate_proxies() {
+	let error = config(vec![(ALICE, BOB, ProxyType::Any), (ALICE, BOB, ProxyType::Any)])
+		.validate()
+		.unwrap_err();
+	assert!(error.contains("twice"), "{error}");
+
+	// The same delegate with another proxy type is fine.
+	assert!(config(vec![(ALICE, BOB, ProxyType::Any), (ALICE, BOB, ProxyType::JustRemarks)])
+		.validate()
+		.is_ok());
+}
+
+#[test]
+fn validate_rejects_too_many_proxies() {
+	let error = config(vec![
+		(ALICE, BOB, ProxyType::Any),
+		(ALICE, BOB, ProxyType::JustRemarks),
+		(ALICE, CHARLIE, ProxyType::Any),
+	])
+	.validate()
+	.unwrap_err();
+	assert!(error.contains(&format!("more than {MAX_PROXIES} proxies")), "{error}");
+}
+
+#[test]
+#[should_panic(expected = "Invalid genesis config: `genesisProxies` makes 1 its own proxy")]
+fn genesis_panics_on_invalid_config() {
+	new_test_ext(config(vec![(ALICE, ALICE, ProxyType::Any)]));
+}
+
+#[test]
+#[should_panic(expected = "Unable to add the genesis proxy 1 of 3")]
+fn genesis_panics_if_the_delegator_cannot_pay_the_deposit() {
+	new_test_ext(config(vec![(CHARLIE, ALICE, ProxyType::Any)]));
+}
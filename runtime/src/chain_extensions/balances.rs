@@ -0,0 +1,101 @@
+//! A chain extension which lets contracts query `pallet_balances` and `frame_system`.
+//!
+//! | `func_id` | Function              | Input       | Output                             |
+//! |-----------|-----------------------|-------------|------------------------------------|
+//! | `0`       | `account`             | `AccountId` | `(Balance, Balance, Balance)`      |
+//! | `1`       | `locks`               | `AccountId` | `Vec<([u8; 8], Balance)>`          |
+//! | `2`       | `reserves`            | `AccountId` | `Vec<([u8; 8], Balance)>`          |
+//! | `3`       | `total_issuance`      |             | `Balance`                          |
+//! | `4`       | `existential_deposit` |             | `Balance`                          |
+//! | `5`       | `parent_hash`         |             | `Hash`                             |
+//! | `6`       | `extrinsic_index`     |             | `Option<u32>`                      |
+//!
+//! `account` returns the free, reserved and frozen balance of an account. `locks` and
+//! `reserves` return the ID and amount of each lock and named reserve of an account.
+//!
+//! Every function is charged a storage read. `locks` and `reserves` read a value which grows
+//! with the number of entries, so they are additionally charged per byte of it, like
+//! `seal_get_storage`.
+
+use codec::Encode;
+use frame_support::{
+	traits::{Get, StoredMap},
+	weights::Weight,
+};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal,
+};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+/// The balances and system query chain extension.
+#[derive(Default)]
+pub struct BalancesExtension;
+
+impl<T> ChainExtension<T> for BalancesExtension
+where
+	T: pallet_contracts::Config + pallet_balances::Config<ReserveIdentifier = [u8; 8]>,
+{
+	fn call<E: Ext<T = T>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+		env.charge_weight(<T as frame_system::Config>::DbWeight::get().reads(1))?;
+
+		let output = match func_id {
+			0 => {
+				let who: T::AccountId = env.read_as()?;
+				let account = <T as pallet_balances::Config>::AccountStore::get(&who);
+				(account.free, account.reserved, account.frozen).encode()
+			},
+			1 => {
+				let who: T::AccountId = env.read_as()?;
+				let locks = pallet_balances::Pallet::<T>::locks(&who);
+				let weight = read_per_byte(env.ext(), locks.encoded_size());
+				env.charge_weight(weight)?;
+				locks
+					.into_iter()
+					.map(|lock| (lock.id, lock.amount))
+					.collect::<Vec<_>>()
+					.encode()
+			},
+			2 => {
+				let who: T::AccountId = env.read_as()?;
+				let reserves = pallet_balances::Pallet::<T>::reserves(&who);
+				let weight = read_per_byte(env.ext(), reserves.encoded_size());
+				env.charge_weight(weight)?;
+				reserves
+					.into_iter()
+					.map(|reserve| (reserve.id, reserve.amount))
+					.collect::<Vec<_>>()
+					.encode()
+			},
+			3 => pallet_balances::Pallet::<T>::total_issuance().encode(),
+			4 => <T as pallet_balances::Config>::ExistentialDeposit::get().encode(),
+			5 => frame_system::Pallet::<T>::parent_hash().encode(),
+			6 => frame_system::Pallet::<T>::extrinsic_index().encode(),
+			_ => return Err(DispatchError::Other("Unknown balances function")),
+		};
+		env.write(&output, false, None)?;
+		Ok(RetVal::Converging(0))
+	}
+}
+
+/// The weight of reading `len` bytes of a storage value, on top of the read itself.
+fn read_per_byte<E: Ext>(ext: &E, len: usize) -> Weight {
+	let len = len as u64;
+	ext.schedule()
+		.host_fn_weights
+		.get_storage_per_byte
+		.saturating_mul(len)
+		.saturating_add(Weight::from_parts(0, len))
+}
+
+impl<T> RegisteredChainExtension<T> for BalancesExtension
+where
+	T: pallet_contracts::Config + pallet_balances::Config<ReserveIdentifier = [u8; 8]>,
+{
+	const ID: u16 = 3;
+}
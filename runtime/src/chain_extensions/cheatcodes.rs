@@ -0,0 +1,124 @@
+//! A chain extension which lets test contracts manipulate their environment.
+//!
+//! It is only enabled if the runtime is built with the `cheatcodes` feature. The functions are
+//! identified by their `func_id`, their input and output is SCALE encoded:
+//!
+//! | `func_id` | Function             | Input                  | Output |
+//! |-----------|----------------------|------------------------|--------|
+//! | `0`       | `set_next_timestamp` | `u64`                  |        |
+//! | `1`       | `set_balance`        | `(AccountId, Balance)` |        |
+//! | `2`       | `impersonate`        | `AccountId`            |        |
+//! | `3`       | `snapshot`           |                        | `u32`  |
+//! | `4`       | `revert`             | `u32`                  |        |
+//!
+//! After `impersonate`, the next call the contract dispatches via `call_runtime` as
+//! `Cheatcodes::dispatch_impersonated` is dispatched as the impersonated account. Calls to
+//! other contracts have to go through `seal_call`, so they are always made by the contract
+//! itself. The status code returned to the contract is one of [`Status`].
+
+use codec::Encode;
+use frame_support::traits::Get;
+use pallet_cheatcodes::{BalanceOf, MomentOf};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal,
+};
+use sp_runtime::DispatchError;
+
+/// The status code returned to the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Status {
+	/// The function succeeded.
+	Success = 0,
+	/// The snapshot passed to `revert` doesn't exist or belongs to another contract.
+	SnapshotNotFound = 1,
+	/// The storage of the contract exceeds the bounds of a snapshot.
+	SnapshotTooLarge = 2,
+}
+
+/// The cheatcodes chain extension.
+#[derive(Default)]
+pub struct CheatcodesExtension;
+
+impl<T> ChainExtension<T> for CheatcodesExtension
+where
+	T: pallet_cheatcodes::Config,
+	MomentOf<T>: From<u64>,
+{
+	fn call<E: Ext<T = T>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+		let db_weight = <T as frame_system::Config>::DbWeight::get();
+		let max_items = <T as pallet_cheatcodes::Config>::MaxSnapshotItems::get() as u64;
+		let snapshot_too_large =
+			DispatchError::from(pallet_cheatcodes::Error::<T>::SnapshotTooLarge);
+
+		let status = match func_id {
+			0 => {
+				env.charge_weight(db_weight.writes(1))?;
+				let timestamp: u64 = env.read_as()?;
+				pallet_cheatcodes::Pallet::<T>::set_next_timestamp(timestamp.into());
+				Status::Success
+			},
+			1 => {
+				env.charge_weight(db_weight.reads_writes(1, 1))?;
+				let (who, amount): (T::AccountId, BalanceOf<T>) = env.read_as()?;
+				pallet_cheatcodes::Pallet::<T>::set_balance(&who, amount);
+				Status::Success
+			},
+			2 => {
+				env.charge_weight(db_weight.writes(1))?;
+				let who: T::AccountId = env.read_as()?;
+				let contract = env.ext().address().clone();
+				pallet_cheatcodes::Pallet::<T>::impersonate(contract, who);
+				Status::Success
+			},
+			// Charges for the largest snapshot up front and refunds the difference.
+			3 => {
+				let charged = env.charge_weight(db_weight.reads_writes(max_items + 2, 2))?;
+				let contract = env.ext().address().clone();
+				match pallet_cheatcodes::Pallet::<T>::snapshot(contract) {
+					Ok((id, len)) => {
+						env.adjust_weight(charged, db_weight.reads_writes(len as u64 + 2, 2));
+						env.write(&id.encode(), false, None)?;
+						Status::Success
+					},
+					Err(e) if e == snapshot_too_large => Status::SnapshotTooLarge,
+					Err(e) => return Err(e),
+				}
+			},
+			// Removes and restores at most `MaxSnapshotItems` items each.
+			4 => {
+				let charged = env.charge_weight(db_weight.reads_writes(2, 2 * max_items))?;
+				let id: u32 = env.read_as()?;
+				let contract = env.ext().address().clone();
+				match pallet_cheatcodes::Pallet::<T>::revert(contract, id) {
+					Ok((removed, restored)) => {
+						let writes = removed as u64 + restored as u64;
+						env.adjust_weight(charged, db_weight.reads_writes(2, writes));
+						Status::Success
+					},
+					Err(e) if e == snapshot_too_large => Status::SnapshotTooLarge,
+					Err(_) => Status::SnapshotNotFound,
+				}
+			},
+			_ => return Err(DispatchError::Other("Unknown cheatcode")),
+		};
+		Ok(RetVal::Converging(status as u32))
+	}
+
+	fn enabled() -> bool {
+		cfg!(feature = "cheatcodes")
+	}
+}
+
+impl<T> RegisteredChainExtension<T> for CheatcodesExtension
+where
+	T: pallet_cheatcodes::Config,
+	MomentOf<T>: From<u64>,
+{
+	const ID: u16 = 1;
+}
@@ -0,0 +1,47 @@
+//! The chain extensions available to contracts.
+//!
+//! Contracts select a chain extension by the `ext_id` of their call, i.e. the upper 16 bits of
+//! the `id` passed to `seal_call_chain_extension`, and a function of it by the lower 16 bits
+//! (`func_id`). Each extension registered in [`Extensions`] claims an ID:
+//!
+//! | ID  | Extension                 |
+//! |-----|---------------------------|
+//! | `1` | [`CheatcodesExtension`]   |
+//! | `2` | [`RandomnessExtension`]   |
+//! | `3` | [`BalancesExtension`]     |
+//! | `4` | [`NftsExtension`]         |
+//!
+//! The `AssetsExtension` of `pallet-assets-chain-extension` is registered under the ID it
+//! declares, which is derived from a hash of its name. `pallet-contracts` routes calls to the
+//! extension of the tuple with their ID and fails calls to any other ID with its
+//! `NoChainExtension` error.
+//!
+//! # Adding an extension
+//!
+//! Extensions are part of the runtime, so adding one means changing the runtime and building
+//! the node again:
+//!
+//! 1. Implement `ChainExtension<Runtime>` for a `Default` type in a new module of this one.
+//! 2. Implement `RegisteredChainExtension<Runtime>` for it, with an ID which isn't claimed yet.
+//! 3. Append it to [`Extensions`] and to the table above.
+//!
+//! Extensions can be enabled conditionally, e.g. by a cargo feature, by overriding
+//! `ChainExtension::enabled`. Calls to the ID of a disabled extension fail like calls to an
+//! unknown ID.
+
+use pallet_assets_chain_extension::substrate::AssetsExtension;
+
+mod balances;
+mod cheatcodes;
+mod nfts;
+mod randomness;
+
+pub use balances::BalancesExtension;
+pub use cheatcodes::CheatcodesExtension;
+pub use nfts::NftsExtension;
+pub use randomness::RandomnessExtension;
+
+/// The chain extensions of the runtime, which are routed to by their ID, see the
+/// [module docs](self).
+pub type Extensions =
+	(CheatcodesExtension, RandomnessExtension, BalancesExtension, NftsExtension, AssetsExtension);
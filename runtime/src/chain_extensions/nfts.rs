@@ -0,0 +1,208 @@
+//! A chain extension which lets contracts mint, transfer and inspect the NFTs of `pallet_nfts`.
+//!
+//! | `func_id` | Function               | Input                                                    | Output              |
+//! |-----------|------------------------|----------------------------------------------------------|---------------------|
+//! | `0`       | `mint`                 | `(CollectionId, ItemId, AccountId)`                      |                     |
+//! | `1`       | `transfer`             | `(CollectionId, ItemId, AccountId)`                      |                     |
+//! | `2`       | `approve_transfer`     | `(CollectionId, ItemId, AccountId, Option<BlockNumber>)` |                     |
+//! | `3`       | `cancel_approval`      | `(CollectionId, ItemId, AccountId)`                      |                     |
+//! | `4`       | `owner`                | `(CollectionId, ItemId)`                                 | `Option<AccountId>` |
+//! | `5`       | `attribute`            | `(CollectionId, ItemId, Vec<u8>)`                        | `Option<Vec<u8>>`   |
+//! | `6`       | `collection_attribute` | `(CollectionId, Vec<u8>)`                                | `Option<Vec<u8>>`   |
+//! | `7`       | `create_collection`    |                                                          | `CollectionId`      |
+//!
+//! Functions `0` to `3` dispatch the call of the same name with the contract as the signed
+//! origin, i.e. the contract has to be the owner, issuer or admin the call requires and pays
+//! its deposits. If the call fails, [`Status::DispatchFailed`] is returned to the contract and
+//! the encoded `DispatchError` is written to its output buffer.
+//!
+//! `attribute` and `collection_attribute` read the attributes set by the owner of the
+//! collection.
+//!
+//! `create_collection` creates a collection with the contract as its owner and admin and all
+//! settings enabled, like `create` dispatched by the contract would. The contract pays the
+//! collection deposit and gets the ID of the collection written to its output buffer, so it can
+//! mint into it right away. If the collection can't be created, it gets the encoded
+//! `DispatchError` and [`Status::DispatchFailed`] instead.
+
+use codec::Encode;
+use frame_support::traits::{
+	tokens::nonfungibles_v2::{Create, Inspect},
+	Get,
+};
+use frame_system::RawOrigin;
+use pallet_contracts::chain_extension::{
+	BufInBufOutState, ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal,
+};
+use pallet_nfts::{
+	CollectionConfig, CollectionConfigFor, CollectionSettings, MintSettings, WeightInfo,
+};
+use sp_runtime::{traits::StaticLookup, DispatchError, DispatchResult};
+use sp_std::vec::Vec;
+
+/// The status code returned to the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Status {
+	/// The function succeeded.
+	Success = 0,
+	/// The dispatched call failed.
+	DispatchFailed = 1,
+}
+
+/// The NFTs chain extension.
+#[derive(Default)]
+pub struct NftsExtension;
+
+impl<T> ChainExtension<T> for NftsExtension
+where
+	T: pallet_contracts::Config + pallet_nfts::Config,
+{
+	fn call<E: Ext<T = T>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+		let db_weight = <T as frame_system::Config>::DbWeight::get();
+
+		let status = match func_id {
+			0 => {
+				env.charge_weight(<T as pallet_nfts::Config>::WeightInfo::mint())?;
+				let (collection, item, mint_to): (T::CollectionId, T::ItemId, T::AccountId) =
+					env.read_as()?;
+				let result = pallet_nfts::Pallet::<T>::mint(
+					signed(&mut env),
+					collection,
+					item,
+					T::Lookup::unlookup(mint_to),
+					None,
+				);
+				dispatched(&mut env, result)?
+			},
+			1 => {
+				env.charge_weight(<T as pallet_nfts::Config>::WeightInfo::transfer())?;
+				let (collection, item, dest): (T::CollectionId, T::ItemId, T::AccountId) =
+					env.read_as()?;
+				let result = pallet_nfts::Pallet::<T>::transfer(
+					signed(&mut env),
+					collection,
+					item,
+					T::Lookup::unlookup(dest),
+				);
+				dispatched(&mut env, result)?
+			},
+			2 => {
+				env.charge_weight(<T as pallet_nfts::Config>::WeightInfo::approve_transfer())?;
+				let (collection, item, delegate, maybe_deadline): (
+					T::CollectionId,
+					T::ItemId,
+					T::AccountId,
+					Option<T::BlockNumber>,
+				) = env.read_as()?;
+				let result = pallet_nfts::Pallet::<T>::approve_transfer(
+					signed(&mut env),
+					collection,
+					item,
+					T::Lookup::unlookup(delegate),
+					maybe_deadline,
+				);
+				dispatched(&mut env, result)?
+			},
+			3 => {
+				env.charge_weight(<T as pallet_nfts::Config>::WeightInfo::cancel_approval())?;
+				let (collection, item, delegate): (T::CollectionId, T::ItemId, T::AccountId) =
+					env.read_as()?;
+				let result = pallet_nfts::Pallet::<T>::cancel_approval(
+					signed(&mut env),
+					collection,
+					item,
+					T::Lookup::unlookup(delegate),
+				);
+				dispatched(&mut env, result)?
+			},
+			4 => {
+				env.charge_weight(db_weight.reads(1))?;
+				let (collection, item): (T::CollectionId, T::ItemId) = env.read_as()?;
+				let owner =
+					<pallet_nfts::Pallet<T> as Inspect<T::AccountId>>::owner(&collection, &item);
+				env.write(&owner.encode(), false, None)?;
+				Status::Success
+			},
+			5 => {
+				env.charge_weight(db_weight.reads(1))?;
+				let len = env.in_len();
+				let (collection, item, key): (T::CollectionId, T::ItemId, Vec<u8>) =
+					env.read_as_unbounded(len)?;
+				let value = <pallet_nfts::Pallet<T> as Inspect<T::AccountId>>::attribute(
+					&collection,
+					&item,
+					&key,
+				);
+				env.write(&value.encode(), false, None)?;
+				Status::Success
+			},
+			6 => {
+				env.charge_weight(db_weight.reads(1))?;
+				let len = env.in_len();
+				let (collection, key): (T::CollectionId, Vec<u8>) = env.read_as_unbounded(len)?;
+				let value = <pallet_nfts::Pallet<T> as Inspect<T::AccountId>>::collection_attribute(
+					&collection,
+					&key,
+				);
+				env.write(&value.encode(), false, None)?;
+				Status::Success
+			},
+			7 => {
+				env.charge_weight(<T as pallet_nfts::Config>::WeightInfo::create())?;
+				let contract = env.ext().address().clone();
+				let config = CollectionConfig {
+					settings: CollectionSettings::all_enabled(),
+					max_supply: None,
+					mint_settings: MintSettings::default(),
+				};
+				let result = <pallet_nfts::Pallet<T> as Create<
+					T::AccountId,
+					CollectionConfigFor<T>,
+				>>::create_collection(&contract, &contract, &config);
+				match result {
+					Ok(collection) => {
+						env.write(&collection.encode(), false, None)?;
+						Status::Success
+					},
+					Err(error) => dispatched(&mut env, Err(error))?,
+				}
+			},
+			_ => return Err(DispatchError::Other("Unknown NFTs function")),
+		};
+		Ok(RetVal::Converging(status as u32))
+	}
+}
+
+impl<T> RegisteredChainExtension<T> for NftsExtension
+where
+	T: pallet_contracts::Config + pallet_nfts::Config,
+{
+	const ID: u16 = 4;
+}
+
+/// The origin of calls dispatched on behalf of the calling contract.
+fn signed<E: Ext>(
+	env: &mut Environment<E, BufInBufOutState>,
+) -> <E::T as frame_system::Config>::RuntimeOrigin {
+	RawOrigin::Signed(env.ext().address().clone()).into()
+}
+
+/// Writes the error of a failed dispatch to the output buffer of the contract.
+fn dispatched<E: Ext>(
+	env: &mut Environment<E, BufInBufOutState>,
+	result: DispatchResult,
+) -> Result<Status, DispatchError> {
+	match result {
+		Ok(()) => Ok(Status::Success),
+		Err(error) => {
+			env.write(&error.encode(), false, None)?;
+			Ok(Status::DispatchFailed)
+		},
+	}
+}
@@ -0,0 +1,46 @@
+//! A chain extension which exposes the runtime's randomness to contracts.
+//!
+//! | `func_id` | Function | Input      | Output                |
+//! |-----------|----------|------------|-----------------------|
+//! | `0`       | `random` | `[u8; 32]` | `(Hash, BlockNumber)` |
+//!
+//! `random` returns a random value for the given subject and the block number since which it
+//! is known, see `frame_support::traits::Randomness`. The randomness isn't secure, as it is
+//! predictable by block authors.
+
+use codec::Encode;
+use frame_support::traits::{Get, Randomness};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal,
+};
+use sp_runtime::DispatchError;
+
+/// The randomness chain extension.
+#[derive(Default)]
+pub struct RandomnessExtension;
+
+impl<T: pallet_contracts::Config> ChainExtension<T> for RandomnessExtension {
+	fn call<E: Ext<T = T>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+
+		match func_id {
+			0 => {
+				// `DevRandomness` reads the fixed random value and the seed.
+				env.charge_weight(<T as frame_system::Config>::DbWeight::get().reads(2))?;
+				let subject: [u8; 32] = env.read_as()?;
+				let random = T::Randomness::random(&subject);
+				env.write(&random.encode(), false, None)?;
+			},
+			_ => return Err(DispatchError::Other("Unknown randomness function")),
+		}
+		Ok(RetVal::Converging(0))
+	}
+}
+
+impl<T: pallet_contracts::Config> RegisteredChainExtension<T> for RandomnessExtension {
+	const ID: u16 = 2;
+}
@@ -6,18 +6,36 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
-use frame_support::dispatch::DispatchClass;
+#[cfg(feature = "runtime-benchmarks")]
+#[macro_use]
+extern crate frame_benchmarking;
+
+mod chain_extensions;
+#[cfg(test)]
+mod tests;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	dispatch::DispatchClass,
+	traits::{Contains, EitherOfDiverse, EqualPrivilegeOnly, Get, InstanceFilter, OnTimestampSet},
+	BoundedVec, RuntimeDebug,
+};
 use frame_system::{
 	limits::{BlockLength, BlockWeights},
 	EnsureSigned,
 };
+use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
+use sp_consensus_aura::{sr25519::AuthorityId as AuraId, Slot, AURA_ENGINE_ID};
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
+	traits::{
+		AccountIdLookup, BlakeTwo256, Block as BlockT, Bounded, ConvertInto, IdentifyAccount,
+		NumberFor, Verify,
+	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, FixedPointNumber, MultiSignature,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -28,8 +46,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU8, KeyOwnerProofSystem,
-		Randomness, StorageInfo,
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8,
+		KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -41,11 +59,19 @@ pub use frame_support::{
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_contracts::Call as ContractsCall;
+pub use pallet_contracts_call_filter::call_index;
+pub use pallet_contracts_limits::ContractLimits;
+pub use pallet_contracts_sponsorship::Call as ContractsSponsorshipCall;
+pub use pallet_dev_randomness::Call as DevRandomnessCall;
+pub use pallet_fee_model::{FeeCoefficient, FeeModel, TargetedAdjustment};
+use pallet_nfts::PalletFeatures;
+pub use pallet_scheduler::Call as SchedulerCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::CurrencyAdapter;
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{Perbill, Permill, Perquintill};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -83,7 +109,10 @@ pub mod opaque {
 	pub type BlockId = generic::BlockId<Block>;
 
 	impl_opaque_keys! {
-		pub struct SessionKeys {}
+		pub struct SessionKeys {
+			pub aura: Aura,
+			pub grandpa: Grandpa,
+		}
 	}
 }
 // To learn more about runtime versioning, see:
@@ -96,12 +125,13 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	// The version of the runtime specification. A full node will not attempt to use its native
 	//   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
-	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
+	// This value was set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 102,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	// Bumped as `ChargeAssetTxPayment` replaced `ChargeTransactionPayment` in `SignedExtra`.
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -111,28 +141,57 @@ pub fn native_version() -> NativeVersion {
 	NativeVersion { runtime_version: VERSION, can_author_with: Default::default() }
 }
 
+/// The block time of chains which are run with Aura and Grandpa instead of instant seal.
+pub const MILLISECS_PER_BLOCK: u64 = 6000;
+
+// NOTE: Currently it is not possible to change the slot duration after the chain has started.
+//       Attempting to do so will brick block production.
+pub const SLOT_DURATION: u64 = MILLISECS_PER_BLOCK;
+
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 
 /// We assume that ~10% of the block weight is consumed by `on_initialize` handlers.
 /// This is used to limit the maximal weight of a single extrinsic.
 const AVERAGE_ON_INITIALIZE_RATIO: Perbill = Perbill::from_percent(10);
 
+/// The maximum size of the proof of validity (PoV) of a parachain block on Polkadot and Kusama.
+pub const MAX_POV_SIZE: u32 = 5 * 1024 * 1024;
+
 /// We allow for 2 seconds of compute with a 6 second average block time, with maximum proof size.
+#[cfg(not(feature = "pov-limits"))]
 const MAXIMUM_BLOCK_WEIGHT: Weight =
 	Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND.saturating_mul(2), u64::MAX);
+/// We allow for 2 seconds of compute with a 6 second average block time. Like on a parachain,
+/// the storage proof of a block is limited to the PoV size.
+#[cfg(feature = "pov-limits")]
+const MAXIMUM_BLOCK_WEIGHT: Weight =
+	Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND.saturating_mul(2), MAX_POV_SIZE as u64);
 
 // Prints debug output of the `contracts` pallet to stdout if the node is
 // started with `-lruntime::contracts=debug`.
+#[cfg(not(feature = "production-parity"))]
 const CONTRACTS_DEBUG_OUTPUT: pallet_contracts::DebugInfo =
 	pallet_contracts::DebugInfo::UnsafeDebug;
+#[cfg(not(feature = "production-parity"))]
 const CONTRACTS_EVENTS: pallet_contracts::CollectEvents =
 	pallet_contracts::CollectEvents::UnsafeCollect;
+// Production chains neither return the debug buffer nor the events of dry runs.
+#[cfg(feature = "production-parity")]
+const CONTRACTS_DEBUG_OUTPUT: pallet_contracts::DebugInfo = pallet_contracts::DebugInfo::Skip;
+#[cfg(feature = "production-parity")]
+const CONTRACTS_EVENTS: pallet_contracts::CollectEvents = pallet_contracts::CollectEvents::Skip;
 
 // Unit = the base number of indivisible units for balances
 const UNIT: Balance = 1_000_000_000_000;
 const MILLIUNIT: Balance = 1_000_000_000;
 pub const EXISTENTIAL_DEPOSIT: Balance = MILLIUNIT;
 
+/// The symbol of the native token, as reported in the chain properties.
+pub const TOKEN_SYMBOL: &str = "UNIT";
+/// The number of decimals of the native token, as reported in the chain properties.
+pub const TOKEN_DECIMALS: u8 = 12;
+const _: () = assert!(UNIT == 10u128.pow(TOKEN_DECIMALS as u32));
+
 const fn deposit(items: u32, bytes: u32) -> Balance {
 	(items as Balance * UNIT + (bytes as Balance) * (5 * MILLIUNIT / 100)) / 10
 }
@@ -140,7 +199,7 @@ const fn deposit(items: u32, bytes: u32) -> Balance {
 fn schedule<T: pallet_contracts::Config>() -> pallet_contracts::Schedule<T> {
 	pallet_contracts::Schedule {
 		limits: pallet_contracts::Limits {
-			runtime_memory: 1024 * 1024 * 1024,
+			runtime_memory: ContractsLimits::limits().runtime_memory,
 			..Default::default()
 		},
 		..Default::default()
@@ -149,6 +208,11 @@ fn schedule<T: pallet_contracts::Config>() -> pallet_contracts::Schedule<T> {
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 
+impl pallet_dev_randomness::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fallback = RandomnessCollectiveFlip;
+}
+
 parameter_types! {
 	pub const BlockHashCount: BlockNumber = 2400;
 	pub const Version: RuntimeVersion = VERSION;
@@ -244,18 +308,72 @@ impl pallet_authorship::Config for Runtime {
 	type EventHandler = ();
 }
 
-parameter_types! {
-	pub const MinimumPeriod: u64 = 5;
+/// The minimum period between blocks authored with instant seal, in milliseconds.
+pub const INSTANT_SEAL_MINIMUM_PERIOD: u64 = 5;
+
+/// Whether the current block is authored by Aura, i.e. carries an Aura slot in its digest.
+///
+/// Development chains are run with instant seal, which authors blocks without a slot.
+fn authored_by_aura() -> bool {
+	System::digest()
+		.logs()
+		.iter()
+		.any(|log| log.pre_runtime_try_to::<Slot>(&AURA_ENGINE_ID).is_some())
+}
+
+/// The minimum period between blocks.
+///
+/// `pallet_aura` derives the slot duration from it, so it is half of `SLOT_DURATION` for blocks
+/// authored by Aura. Instant seal authors blocks in quick succession, which requires a far
+/// shorter period.
+pub struct MinimumPeriod;
+
+impl Get<u64> for MinimumPeriod {
+	fn get() -> u64 {
+		if authored_by_aura() {
+			SLOT_DURATION / 2
+		} else {
+			INSTANT_SEAL_MINIMUM_PERIOD
+		}
+	}
+}
+
+/// Checks that the timestamp matches the Aura slot of the block, for blocks authored by Aura.
+pub struct AuraOnTimestampSet;
+
+impl OnTimestampSet<u64> for AuraOnTimestampSet {
+	fn on_timestamp_set(moment: u64) {
+		if authored_by_aura() {
+			<Aura as OnTimestampSet<u64>>::on_timestamp_set(moment)
+		}
+	}
 }
 
 impl pallet_timestamp::Config for Runtime {
 	/// A timestamp: milliseconds since the unix epoch.
 	type Moment = u64;
-	type OnTimestampSet = ();
+	type OnTimestampSet = AuraOnTimestampSet;
 	type MinimumPeriod = MinimumPeriod;
 	type WeightInfo = ();
 }
 
+impl pallet_aura::Config for Runtime {
+	type AuthorityId = AuraId;
+	type DisabledValidators = ();
+	type MaxAuthorities = ConstU32<32>;
+}
+
+impl pallet_grandpa::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+
+	type WeightInfo = ();
+	type MaxAuthorities = ConstU32<32>;
+	type MaxSetIdSessionEntries = ConstU64<0>;
+
+	type KeyOwnerProof = sp_core::Void;
+	type EquivocationReportSystem = ();
+}
+
 parameter_types! {
 	pub const MaxLocks: u32 = 50;
 }
@@ -280,11 +398,77 @@ impl pallet_balances::Config for Runtime {
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	// Doesn't charge any fees in fee-less mode, see `DevFees`.
+	type OnChargeTransaction = pallet_dev_fees::FeelessAdapter<Self, CurrencyAdapter<Balances, ()>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
-	type WeightToFee = IdentityFee<Balance>;
+	// The weight to fee polynomial and the fee multiplier can be changed in the chain spec or
+	// via `TransactionFees::set_fee_model`.
+	type WeightToFee = pallet_fee_model::WeightToFee<Self>;
 	type LengthToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ();
+	type FeeMultiplierUpdate = pallet_fee_model::FeeMultiplierUpdate<Self>;
+}
+
+/// The fee model applied if none is set in the chain spec.
+///
+/// By default, fees are the weight of a transaction and don't react to congestion. With the
+/// `production-parity` feature, they follow the model of the Polkadot relay chain: a base
+/// extrinsic costs a tenth of a cent and the fee multiplier targets blocks which are a quarter
+/// full.
+fn default_fee_model() -> FeeModel<Balance> {
+	let coefficient = |coeff_integer, coeff_frac| {
+		BoundedVec::truncate_from(vec![FeeCoefficient {
+			coeff_integer,
+			coeff_frac,
+			negative: false,
+			degree: 1,
+		}])
+	};
+	if cfg!(feature = "production-parity") {
+		let p = CENTS;
+		let q = 10 * Balance::from(ExtrinsicBaseWeight::get().ref_time());
+		FeeModel {
+			multiplier: Some(TargetedAdjustment {
+				target_block_fullness: Perquintill::from_percent(25),
+				adjustment_variable: Multiplier::saturating_from_rational(75, 1_000_000),
+				minimum_multiplier: Multiplier::saturating_from_rational(1, 10u128),
+				maximum_multiplier: Bounded::max_value(),
+			}),
+			weight_to_fee: coefficient(p / q, Perbill::from_rational(p % q, q)),
+		}
+	} else {
+		FeeModel { multiplier: None, weight_to_fee: coefficient(1, Perbill::zero()) }
+	}
+}
+
+parameter_types! {
+	pub DefaultFeeModel: FeeModel<Balance> = default_fee_model();
+}
+
+impl pallet_fee_model::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type DefaultFeeModel = DefaultFeeModel;
+}
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	// Fees paid in an asset are converted at the ratio of the asset's minimum balance to the
+	// existential deposit, so only sufficient assets can be used. Like native fees, they are
+	// burned, and refunded in fee-less mode.
+	type OnChargeAssetTransaction = pallet_dev_fees::FeelessAssetAdapter<
+		Self,
+		pallet_asset_tx_payment::FungiblesAdapter<AssetFeeConversion, ()>,
+		AssetFeeConversion,
+	>;
+}
+
+/// Converts fees into sufficient assets at the ratio of their minimum balance to the existential
+/// deposit.
+type AssetFeeConversion = pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>;
+
+impl pallet_dev_fees::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -324,13 +508,130 @@ impl pallet_assets::Config for Runtime {
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
 	type RemoveItemsLimit = ConstU32<1000>;
 	type CallbackHandle = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
 }
 
+parameter_types! {
+	pub const CollectionDeposit: Balance = 100 * DOLLARS;
+	pub const ItemDeposit: Balance = 1 * DOLLARS;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 256;
+	pub const ApprovalsLimit: u32 = 20;
+	pub const ItemAttributesApprovalsLimit: u32 = 20;
+	pub const MaxTips: u32 = 10;
+	// About 30 days at 6 second blocks.
+	pub const MaxDeadlineDuration: BlockNumber = 30 * 24 * 60 * 10;
+	pub const MaxAttributesPerCall: u32 = 10;
+	pub Features: PalletFeatures = PalletFeatures::all_enabled();
+}
+
+impl pallet_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type Locker = ();
+	type CollectionDeposit = CollectionDeposit;
+	type ItemDeposit = ItemDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = MetadataDepositBase;
+	type DepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type ApprovalsLimit = ApprovalsLimit;
+	type ItemAttributesApprovalsLimit = ItemAttributesApprovalsLimit;
+	type MaxTips = MaxTips;
+	type MaxDeadlineDuration = MaxDeadlineDuration;
+	type MaxAttributesPerCall = MaxAttributesPerCall;
+	type Features = Features;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type WeightInfo = pallet_nfts::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type Helper = ();
+}
+
+impl pallet_genesis_nfts::Config for Runtime {}
+
 parameter_types! {
 	pub const DepositPerItem: Balance = deposit(1, 0);
 	pub const DepositPerByte: Balance = deposit(0, 1);
 	pub Schedule: pallet_contracts::Schedule<Runtime> = schedule::<Runtime>();
-	pub const DefaultDepositLimit: Balance = deposit(1024, 1024 * 1024);
+	// This node is geared towards development and testing of contracts.
+	// We decided to increase the default allowed contract size for this
+	// reason (the default is `128 * 1024`).
+	//
+	// Our reasoning is that the error code `CodeTooLarge` is thrown
+	// if a too-large contract is uploaded. We noticed that it poses
+	// less friction during development when the requirement here is
+	// just more lax.
+	//
+	// All of these limits can be changed in the chain spec or via `ContractsLimits::set_limits`.
+	pub const DefaultContractLimits: ContractLimits<Balance> = ContractLimits {
+		runtime_memory: if cfg!(feature = "production-parity") {
+			// The default of `pallet_contracts::Limits`.
+			128 * 1024 * 1024
+		} else {
+			1024 * 1024 * 1024
+		},
+		// `kitchensink` allows 123 KiB, the most its runtime memory and call stack accommodate.
+		max_code_len: if cfg!(feature = "production-parity") { 123 * 1024 } else { 256 * 1024 },
+		max_debug_buffer_len: 2 * 1024 * 1024,
+		default_deposit_limit: deposit(1024, 1024 * 1024),
+	};
+}
+
+/// The depth of the call stack of production chains, e.g. Substrate's `kitchensink` runtime.
+#[cfg(feature = "production-parity")]
+const CONTRACTS_CALL_STACK_DEPTH: u32 = 5;
+#[cfg(not(feature = "production-parity"))]
+const CONTRACTS_CALL_STACK_DEPTH: u32 = 23;
+
+type ContractsCallStack = [pallet_contracts::Frame<Runtime>; CONTRACTS_CALL_STACK_DEPTH as usize];
+
+/// The length of the longest code stored by `pallet-contracts`, below which `ContractsLimits`
+/// doesn't lower the maximum code length.
+pub struct ContractsMaxStoredCodeLen;
+
+impl Get<u32> for ContractsMaxStoredCodeLen {
+	fn get() -> u32 {
+		use codec::Compact;
+		use frame_support::storage::{storage_prefix, PrefixIterator};
+
+		// The code is read raw, as code exceeding `MaxCodeLen` doesn't decode.
+		fn code_len(mut value: &[u8], skip: usize) -> Result<u32, codec::Error> {
+			for _ in 0..skip {
+				Compact::<u32>::decode(&mut value)?;
+			}
+			Ok(Compact::<u32>::decode(&mut value)?.0)
+		}
+		let max_len = |item: &[u8], decode_fn: fn(&[u8], &[u8]) -> Result<u32, codec::Error>| {
+			let prefix = storage_prefix(b"Contracts", item).to_vec();
+			PrefixIterator::<u32>::new(prefix.clone(), prefix, decode_fn).max().unwrap_or(0)
+		};
+
+		// The instrumented code is preceded by three compact fields of `PrefabWasmModule`.
+		max_len(b"PristineCode", |_, value| code_len(value, 0))
+			.max(max_len(b"CodeStorage", |_, value| code_len(value, 3)))
+	}
+}
+
+parameter_types! {
+	pub const ContractsCallStackDepth: u32 = CONTRACTS_CALL_STACK_DEPTH;
+	pub ContractsMaxHeapSize: u32 = pallet_contracts::Limits::default().max_memory_size();
+}
+
+impl pallet_contracts_limits::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type DefaultLimits = DefaultContractLimits;
+	type CallStackDepth = ContractsCallStackDepth;
+	type MaxHeapSize = ContractsMaxHeapSize;
+	type MaxStoredCodeLen = ContractsMaxStoredCodeLen;
 }
 
 impl pallet_utility::Config for Runtime {
@@ -340,17 +641,205 @@ impl pallet_utility::Config for Runtime {
 	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
 }
 
-pub enum AllowBalancesCall {}
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = 1 * DOLLARS;
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight =
+		Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	// Signed origins, including contracts via `call_runtime`, may schedule calls, e.g.
+	// `Contracts::call`. Scheduled calls are dispatched with the origin which scheduled them.
+	type ScheduleOrigin =
+		EitherOfDiverse<frame_system::EnsureRoot<AccountId>, EnsureSigned<AccountId>>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
 
-impl frame_support::traits::Contains<RuntimeCall> for AllowBalancesCall {
+/// The kinds of calls a proxy may make on behalf of its delegator.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProxyType {
+	/// All calls.
+	Any,
+	/// Calls of `Contracts`, e.g. to instantiate, call or upgrade contracts, and batches of them.
+	Contracts,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, call: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// The calls in a batch are filtered individually.
+			ProxyType::Contracts =>
+				matches!(call, RuntimeCall::Contracts(..) | RuntimeCall::Utility(..)),
+		}
+	}
+
+	fn is_superset(&self, other: &Self) -> bool {
+		match (self, other) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+impl pallet_genesis_proxies::Config for Runtime {
+	type GenesisProxyType = ProxyType;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const MultisigDepositBase: Balance = deposit(1, 88);
+	// Additional storage item size of 32 bytes.
+	pub const MultisigDepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = MultisigDepositBase;
+	type DepositFactor = MultisigDepositFactor;
+	type MaxSignatories = ConstU32<100>;
+	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxSnapshotItems: u32 = 128;
+	// Keys are hashed with `Blake2_128Concat`.
+	pub const MaxSnapshotKeyLen: u32 = 128 + 16;
+	pub MaxSnapshotValueLen: u32 = pallet_contracts::Limits::default().payload_len;
+}
+
+// The pallet is only part of the runtime with the `cheatcodes` feature. Its config is kept
+// regardless, as the disabled cheatcodes chain extension is still registered.
+impl pallet_cheatcodes::Config for Runtime {
+	type RuntimeCall = RuntimeCall;
+	type Timestamp = Timestamp;
+	type MaxSnapshotItems = MaxSnapshotItems;
+	type MaxSnapshotKeyLen = MaxSnapshotKeyLen;
+	type MaxSnapshotValueLen = MaxSnapshotValueLen;
+}
+
+impl pallet_contracts_call_filter::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+}
+
+impl pallet_contracts_sponsorship::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Feeless = pallet_dev_fees::IsFeeless<Self>;
+}
+
+/// The calls contracts may dispatch via `call_runtime`, i.e. the calls on the allowlist of
+/// `ContractsCallFilter`.
+///
+/// The scheduler dispatches scheduled calls without the filter of the origin which scheduled
+/// them, so a call contracts schedule has to be allowed as well. The exception is
+/// `Contracts::call`, which contracts may not dispatch directly, as it would enter a contract
+/// on a call stack of its own. Scheduled, it runs in a later block, so contracts may schedule
+/// calls to themselves.
+///
+/// With the `cheatcodes` feature, contracts may dispatch any allowed call as the account they
+/// impersonate via `Cheatcodes::dispatch_impersonated`.
+pub struct AllowedContractsCalls;
+
+impl Contains<RuntimeCall> for AllowedContractsCalls {
 	fn contains(call: &RuntimeCall) -> bool {
-		matches!(call, RuntimeCall::Balances(BalancesCall::transfer_allow_death { .. }))
+		#[cfg(feature = "cheatcodes")]
+		if let RuntimeCall::Cheatcodes(pallet_cheatcodes::Call::dispatch_impersonated { call }) =
+			call
+		{
+			return Self::contains(call)
+		}
+		let scheduled = match call {
+			RuntimeCall::Scheduler(
+				SchedulerCall::schedule { call, .. } |
+				SchedulerCall::schedule_named { call, .. } |
+				SchedulerCall::schedule_after { call, .. } |
+				SchedulerCall::schedule_named_after { call, .. },
+			) => Some(call),
+			_ => None,
+		};
+		pallet_contracts_call_filter::AllowedCallFilter::<Runtime>::contains(call) &&
+			scheduled.map_or(true, |call| {
+				matches!(**call, RuntimeCall::Contracts(ContractsCall::call { .. })) ||
+					Self::contains(call)
+			})
 	}
 }
 
 impl pallet_contracts::Config for Runtime {
+	// Contracts observe the timestamp set via the cheatcodes chain extension, if any.
+	#[cfg(feature = "cheatcodes")]
+	type Time = pallet_cheatcodes::OverridableTime<Self>;
+	#[cfg(not(feature = "cheatcodes"))]
 	type Time = Timestamp;
-	type Randomness = RandomnessCollectiveFlip;
+	// Deterministic if a seed is set, otherwise `RandomnessCollectiveFlip`.
+	type Randomness = DevRandomness;
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -361,28 +850,28 @@ impl pallet_contracts::Config for Runtime {
 	/// and make sure they are stable. Dispatchables exposed to contracts are not allowed to
 	/// change because that would break already deployed contracts. The `RuntimeCall` structure
 	/// itself is not allowed to change the indices of existing pallets, too.
-	type CallFilter = AllowBalancesCall;
+	///
+	/// This node keeps the whitelist in storage, so it can be seeded from the chain spec and
+	/// edited via `sudo`. By default only `Balances::transfer_allow_death`, the calls of
+	/// `Scheduler` to schedule and cancel calls and `ContractsSponsorship::set_budget` are
+	/// allowed. See [`AllowedContractsCalls`] for scheduled calls.
+	type CallFilter = AllowedContractsCalls;
 	type DepositPerItem = DepositPerItem;
 	type DepositPerByte = DepositPerByte;
-	type CallStack = [pallet_contracts::Frame<Self>; 23];
+	// The depth of the call stack is part of the type and therefore can't be configured
+	// via `ContractsLimits`.
+	type CallStack = ContractsCallStack;
 	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
 	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-	type ChainExtension = pallet_assets_chain_extension::substrate::AssetsExtension;
+	type ChainExtension = chain_extensions::Extensions;
 	type Schedule = Schedule;
 	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
-	// This node is geared towards development and testing of contracts.
-	// We decided to increase the default allowed contract size for this
-	// reason (the default is `128 * 1024`).
-	//
-	// Our reasoning is that the error code `CodeTooLarge` is thrown
-	// if a too-large contract is uploaded. We noticed that it poses
-	// less friction during development when the requirement here is
-	// just more lax.
-	type MaxCodeLen = ConstU32<{ 256 * 1024 }>;
-	type DefaultDepositLimit = DefaultDepositLimit;
+	type MaxCodeLen = pallet_contracts_limits::MaxCodeLen<Self>;
+	type DefaultDepositLimit = pallet_contracts_limits::DefaultDepositLimit<Self>;
 	type MaxStorageKeyLen = ConstU32<128>;
-	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
-	type UnsafeUnstableInterface = ConstBool<true>;
+	type MaxDebugBufferLen = pallet_contracts_limits::MaxDebugBufferLen<Self>;
+	// Production chains don't expose unstable host functions to contracts.
+	type UnsafeUnstableInterface = ConstBool<{ !cfg!(feature = "production-parity") }>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -403,6 +892,25 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		Contracts: pallet_contracts,
 		Assets: pallet_assets,
+		Aura: pallet_aura,
+		Grandpa: pallet_grandpa,
+		ContractsLimits: pallet_contracts_limits,
+		ContractsCallFilter: pallet_contracts_call_filter,
+		DevRandomness: pallet_dev_randomness,
+		Nfts: pallet_nfts,
+		GenesisNfts: pallet_genesis_nfts,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
+		Proxy: pallet_proxy,
+		Multisig: pallet_multisig,
+		GenesisProxies: pallet_genesis_proxies,
+		AssetTxPayment: pallet_asset_tx_payment,
+		DevFees: pallet_dev_fees,
+		ContractsSponsorship: pallet_contracts_sponsorship,
+		TransactionFees: pallet_fee_model,
+		// Last, so that the indices of the other pallets don't depend on the feature.
+		#[cfg(feature = "cheatcodes")]
+		Cheatcodes: pallet_cheatcodes,
 	}
 );
 
@@ -421,7 +929,11 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	// Charges the fees of calls to contracts which sponsor their callers to the contract.
+	pallet_contracts_sponsorship::Sponsored<
+		Runtime,
+		pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
+	>,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
@@ -438,6 +950,44 @@ pub type Executive = frame_executive::Executive<
 	pallet_contracts::Migration<Runtime>,
 >;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benches {
+	define_benchmarks!(
+		[frame_benchmarking, BaselineBench::<Runtime>]
+		[frame_system, SystemBench::<Runtime>]
+		[pallet_balances, Balances]
+		[pallet_timestamp, Timestamp]
+		[pallet_utility, Utility]
+		[pallet_sudo, Sudo]
+		[pallet_assets, Assets]
+		[pallet_nfts, Nfts]
+		[pallet_preimage, Preimage]
+		[pallet_scheduler, Scheduler]
+		[pallet_proxy, Proxy]
+		[pallet_multisig, Multisig]
+		[pallet_contracts, Contracts]
+	);
+}
+
+sp_api::decl_runtime_apis! {
+	/// Derives contract addresses via the `AddressGenerator` of `pallet_contracts`, so the node
+	/// derives them the way the runtime on chain does.
+	pub trait ContractsAddressApi<AccountId, Hash>
+	where
+		AccountId: codec::Codec,
+		Hash: codec::Codec,
+	{
+		/// Returns the address of the contract `deployer` instantiates from the code `code_hash`
+		/// with the constructor input `input_data` and the salt `salt`.
+		fn contract_address(
+			deployer: AccountId,
+			code_hash: Hash,
+			input_data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> AccountId;
+	}
+}
+
 type EventRecord = frame_system::EventRecord<
 	<Runtime as frame_system::Config>::RuntimeEvent,
 	<Runtime as frame_system::Config>::Hash,
@@ -520,6 +1070,46 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
+		fn slot_duration() -> sp_consensus_aura::SlotDuration {
+			sp_consensus_aura::SlotDuration::from_millis(SLOT_DURATION)
+		}
+
+		fn authorities() -> Vec<AuraId> {
+			Aura::authorities().into_inner()
+		}
+	}
+
+	impl sp_consensus_grandpa::GrandpaApi<Block> for Runtime {
+		fn grandpa_authorities() -> sp_consensus_grandpa::AuthorityList {
+			Grandpa::grandpa_authorities()
+		}
+
+		fn current_set_id() -> sp_consensus_grandpa::SetId {
+			Grandpa::current_set_id()
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			_equivocation_proof: sp_consensus_grandpa::EquivocationProof<
+				<Block as BlockT>::Hash,
+				NumberFor<Block>,
+			>,
+			_key_owner_proof: sp_consensus_grandpa::OpaqueKeyOwnershipProof,
+		) -> Option<()> {
+			None
+		}
+
+		fn generate_key_ownership_proof(
+			_set_id: sp_consensus_grandpa::SetId,
+			_authority_id: GrandpaId,
+		) -> Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof> {
+			// NOTE: this is the only implementation possible since we've
+			// defined our key owner proof type as a bottom type (i.e. a type
+			// with no values).
+			None
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
 		fn account_nonce(account: AccountId) -> Index {
 			System::account_nonce(account)
@@ -634,4 +1224,85 @@ impl_runtime_apis! {
 			Contracts::get_storage(address, key)
 		}
 	}
+
+	impl self::ContractsAddressApi<Block, AccountId, Hash> for Runtime {
+		fn contract_address(
+			deployer: AccountId,
+			code_hash: Hash,
+			input_data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> AccountId {
+			use pallet_contracts::AddressGenerator;
+
+			<Runtime as pallet_contracts::Config>::AddressGenerator::contract_address(
+				&deployer,
+				&code_hash,
+				&input_data,
+				&salt,
+			)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
+			// NOTE: intentional unwrap: we don't want to propagate the error backwards, and want to
+			// have a backtrace here. If any of the pre/post migration checks fail, we shall stop
+			// right here and right now.
+			let weight = Executive::try_runtime_upgrade(checks).unwrap();
+			(weight, RuntimeBlockWeights::get().max_block)
+		}
+
+		fn execute_block(
+			block: Block,
+			state_root_check: bool,
+			signature_check: bool,
+			select: frame_try_runtime::TryStateSelect
+		) -> Weight {
+			// NOTE: intentional unwrap: we don't want to propagate the error backwards, and want to
+			// have a backtrace here.
+			Executive::try_execute_block(block, state_root_check, signature_check, select).expect("execute-block failed")
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn benchmark_metadata(extra: bool) -> (
+			Vec<frame_benchmarking::BenchmarkList>,
+			Vec<frame_support::traits::StorageInfo>,
+		) {
+			use frame_benchmarking::{baseline, Benchmarking, BenchmarkList};
+			use frame_support::traits::StorageInfoTrait;
+			use frame_system_benchmarking::Pallet as SystemBench;
+			use baseline::Pallet as BaselineBench;
+
+			let mut list = Vec::<BenchmarkList>::new();
+			list_benchmarks!(list, extra);
+
+			let storage_info = AllPalletsWithSystem::storage_info();
+
+			(list, storage_info)
+		}
+
+		fn dispatch_benchmark(
+			config: frame_benchmarking::BenchmarkConfig
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{baseline, Benchmarking, BenchmarkBatch, TrackedStorageKey};
+
+			use frame_system_benchmarking::Pallet as SystemBench;
+			use baseline::Pallet as BaselineBench;
+
+			impl frame_system_benchmarking::Config for Runtime {}
+			impl baseline::Config for Runtime {}
+
+			use frame_support::traits::WhitelistedStorageKeys;
+			let whitelist: Vec<TrackedStorageKey> = AllPalletsWithSystem::whitelisted_storage_keys();
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&config, &whitelist);
+			add_benchmarks!(params, batches);
+
+			Ok(batches)
+		}
+	}
 }
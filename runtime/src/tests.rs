@@ -0,0 +1,92 @@
+use crate::{
+	call_index, AllowedContractsCalls, BalancesCall, ContractsCall, ContractsCallFilter,
+	RuntimeCall, RuntimeOrigin, SchedulerCall, SystemCall,
+};
+use frame_support::{assert_ok, traits::Contains};
+use sp_runtime::AccountId32;
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(SystemCall::remark { remark: vec![] })
+}
+
+fn transfer() -> RuntimeCall {
+	RuntimeCall::Balances(BalancesCall::transfer_allow_death {
+		dest: AccountId32::new([1; 32]).into(),
+		value: 1,
+	})
+}
+
+fn contracts_call() -> RuntimeCall {
+	RuntimeCall::Contracts(ContractsCall::call {
+		dest: AccountId32::new([1; 32]).into(),
+		value: 0,
+		gas_limit: Default::default(),
+		storage_deposit_limit: None,
+		data: vec![],
+	})
+}
+
+fn schedule(call: RuntimeCall) -> RuntimeCall {
+	RuntimeCall::Scheduler(SchedulerCall::schedule_after {
+		after: 1,
+		maybe_periodic: None,
+		priority: 0,
+		call: Box::new(call),
+	})
+}
+
+fn allow(call: &RuntimeCall) {
+	let (pallet_index, index) = call_index(call).unwrap();
+	assert_ok!(ContractsCallFilter::allow_call(RuntimeOrigin::root(), pallet_index, index));
+}
+
+#[test]
+fn contracts_may_only_schedule_allowed_calls() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		allow(&transfer());
+		allow(&schedule(remark()));
+
+		assert!(AllowedContractsCalls::contains(&transfer()));
+		assert!(AllowedContractsCalls::contains(&schedule(transfer())));
+		// Scheduling a disallowed call doesn't bypass the filter.
+		assert!(!AllowedContractsCalls::contains(&remark()));
+		assert!(!AllowedContractsCalls::contains(&schedule(remark())));
+		assert!(!AllowedContractsCalls::contains(&schedule(schedule(remark()))));
+	});
+}
+
+#[test]
+fn contracts_may_schedule_calls_to_contracts() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		allow(&schedule(remark()));
+
+		assert!(!AllowedContractsCalls::contains(&contracts_call()));
+		assert!(AllowedContractsCalls::contains(&schedule(contracts_call())));
+	});
+}
+
+#[test]
+fn scheduling_requires_the_scheduler_to_be_allowed() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		allow(&transfer());
+
+		assert!(!AllowedContractsCalls::contains(&schedule(transfer())));
+	});
+}
+
+#[cfg(feature = "cheatcodes")]
+#[test]
+fn contracts_may_only_dispatch_allowed_calls_as_impersonated_accounts() {
+	let impersonated = |call: RuntimeCall| {
+		RuntimeCall::Cheatcodes(pallet_cheatcodes::Call::dispatch_impersonated {
+			call: Box::new(call),
+		})
+	};
+	sp_io::TestExternalities::default().execute_with(|| {
+		allow(&transfer());
+
+		assert!(AllowedContractsCalls::contains(&impersonated(transfer())));
+		assert!(!AllowedContractsCalls::contains(&impersonated(remark())));
+		assert!(!AllowedContractsCalls::contains(&impersonated(contracts_call())));
+	});
+}